@@ -0,0 +1,66 @@
+use bevy::ecs::{component::Component, world::World};
+use bevy_serde_lens::format::{Json, Postcard};
+use bevy_serde_lens::{BevyObject, WorldExtension};
+use criterion::{Criterion, criterion_group, criterion_main};
+use rand::distributions::{Distribution, Standard};
+use rand_derive2::RandGen;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Component, Serialize, Deserialize, RandGen)]
+pub struct Character(String);
+
+#[derive(Debug, Clone, Component, Serialize, Deserialize, RandGen)]
+pub struct Bio {
+    pub clan: String,
+    pub age: u32,
+    pub height: f32,
+    pub hobbies: String,
+}
+
+#[derive(BevyObject)]
+#[bevy_object(parallel)]
+pub struct Person {
+    character: Character,
+    bio: Bio,
+}
+
+fn many_of<T>(count: usize) -> Vec<T>
+where
+    Standard: Distribution<T>,
+{
+    (0..count).map(|_| rand::random()).collect()
+}
+
+pub fn bench_parallel(c: &mut Criterion) {
+    let mut world = World::new();
+    world.spawn_batch(many_of::<(Character, Bio)>(200000));
+    c.bench_function("json_sequential", |b| {
+        b.iter(|| world.save_to_bytes::<Person>(Json).unwrap());
+    });
+    c.bench_function("json_parallel", |b| {
+        b.iter(|| world.save_parallel::<Person>(Json).unwrap());
+    });
+    c.bench_function("postcard_sequential", |b| {
+        b.iter(|| world.save_to_bytes::<Person>(Postcard).unwrap());
+    });
+    c.bench_function("postcard_parallel", |b| {
+        b.iter(|| world.save_parallel::<Person>(Postcard).unwrap());
+    });
+    let json = world.save_to_bytes::<Person>(Json).unwrap();
+    let mut target = World::new();
+    c.bench_function("json_load_sequential", |b| {
+        b.iter(|| {
+            target.load_from_bytes::<Person>(&json, Json).unwrap();
+            target.clear_entities();
+        });
+    });
+    c.bench_function("json_load_parallel", |b| {
+        b.iter(|| {
+            target.load_parallel::<Person>(&json, Json).unwrap();
+            target.clear_entities();
+        });
+    });
+}
+
+criterion_group!(benches, bench_parallel);
+criterion_main!(benches);
@@ -34,4 +34,11 @@ impl ScopeUtils {
         let _entity = DespawnEntity(ENTITY.replace(Some(entity)));
         f()
     }
+
+    /// Replace the current entity until the end of the innermost
+    /// [`ScopeUtils::current_entity_scope`].
+    #[inline(always)]
+    pub fn set_current_entity(entity: Entity) {
+        ENTITY.set(Some(entity))
+    }
 }
@@ -3,8 +3,9 @@ use proc_macro2::{Span, TokenStream, TokenTree};
 use proc_macro_error::{abort, proc_macro_error};
 use quote::{format_ident, quote, ToTokens};
 use syn::{
-    punctuated::Punctuated, spanned::Spanned, Attribute, Data, DeriveInput, Expr, Lit, Meta, Path,
-    Token,
+    parse_quote, punctuated::Punctuated, spanned::Spanned, Attribute, Data, DataEnum, DeriveInput,
+    Expr, Fields, GenericArgument, GenericParam, Generics, Ident, Index, Lit, Meta, Path,
+    PathArguments, Token, Type, WhereClause,
 };
 
 /// Derive macro for `BevyObject`. This largely mirrors `Bundle` but supports additional types of fields.
@@ -24,6 +25,17 @@ use syn::{
 /// Assert the type can be serialized from a single query (no children, i.e `Child` and `ChildVec`).
 /// This speeds up serialization.
 ///
+/// Fields using `Child`, `ChildVec` or `ChildMap`, including inside `Maybe`, are rejected.
+/// This is not inferred since nested `BevyObject`s may not support `query`.
+///
+/// Types are matched by name if unqualified or under `bevy_serde_lens`,
+/// components with the same names can be used with a qualified path like `self::Child`.
+///
+/// * `#[bevy_object(parallel)]`
+///
+/// Implies `query`, also implement `OwnedObject` so the type can be loaded with `load_parallel`.
+/// All fields must be `OwnedObject`s, i.e. components or other `parallel` objects.
+///
 /// * `#[bevy_object(rename = "Name")]`
 ///
 /// Change the serialized name of this type.
@@ -34,6 +46,25 @@ use syn::{
 ///
 /// Expects `fn(&mut World) -> Option<EntityWorldMut>`.
 ///
+/// # Tuple Structs
+///
+/// Tuple structs are serialized as sequences,
+/// or as the inner value if there is only one field, like serde.
+///
+/// # Generics
+///
+/// Generic parameters and where clauses are supported on structs.
+/// Note all instances share the same `name`.
+///
+/// # Enums
+///
+/// Enums with struct variants describe mutually exclusive layouts of the same object.
+/// During serialization, the first variant whose filter matches the entity is chosen
+/// and serialized as an externally tagged value.
+/// During deserialization, components of the chosen variant are inserted.
+///
+/// `query` and `parallel` are not supported on enums.
+///
 /// # Field Attributes
 ///
 /// * `#[bevy_object(no_filter)]`
@@ -41,6 +72,16 @@ use syn::{
 /// Ignore the `QueryFilter` generated by this field,
 /// this is useful for validating data integrity during serialization.
 ///
+/// * `#[bevy_object(with = "module")]`
+///
+/// Serialize a component with `serialize` and `deserialize` functions in a module, like `#[serde(with)]`.
+/// The field keeps its component type. The component type cannot depend on generic parameters.
+///
+/// * `#[bevy_object(flatten)]`
+///
+/// Embed fields of a nested `BevyObject` into this object, like `#[serde(flatten)]`.
+/// Not supported on tuple structs.
+///
 /// # Serde Attributes
 ///
 /// You can specify serde attributes `#[serde]` in this macro but you don't need to actually derive serde.
@@ -80,6 +121,7 @@ fn parse_attr(attr: &Attribute, name: &str) -> bool {
 fn parse_attr_main(
     attr: &Attribute,
     query: &mut bool,
+    parallel: &mut bool,
     name: &mut String,
     parent: &mut Option<Path>,
 ) {
@@ -95,6 +137,10 @@ fn parse_attr_main(
             Meta::Path(path) if path.is_ident("query") => {
                 *query = true;
             }
+            Meta::Path(path) if path.is_ident("parallel") => {
+                *query = true;
+                *parallel = true;
+            }
             Meta::NameValue(meta) if meta.path.is_ident("rename") => {
                 let Expr::Lit(lit) = meta.value else { continue };
                 let Lit::Str(lit) = lit.lit else { continue };
@@ -130,44 +176,126 @@ fn serialization_archetype2(tokens: TokenStream) -> TokenStream {
         abort!(Span::call_site(), "Invalid input.")
     };
 
-    let Data::Struct(st) = result.data else {
-        abort!(result.span(), "Invalid struct.")
+    let st = match result.data {
+        Data::Struct(st) => st,
+        Data::Enum(_) if !result.generics.params.is_empty() => {
+            abort!(result.generics.span(), "Generic enums are not supported.")
+        }
+        Data::Enum(data) => return enum_archetype(result.ident, result.attrs, data),
+        Data::Union(_) => abort!(result.ident.span(), "Invalid struct."),
     };
 
     let name = result.ident;
     let mut name_str = name.to_string();
     let mut parent = None;
     let mut is_query = false;
+    let mut is_parallel = false;
 
     for attr in &result.attrs {
-        parse_attr_main(attr, &mut is_query, &mut name_str, &mut parent);
+        parse_attr_main(
+            attr,
+            &mut is_query,
+            &mut is_parallel,
+            &mut name_str,
+            &mut parent,
+        );
     }
 
+    let generics = result.generics;
+    let is_generic = !generics.params.is_empty();
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let is_tuple = matches!(st.fields, Fields::Unnamed(_));
     let name_binding = format_ident!("{name}Binding");
+    let name_owned = format_ident!("{name}Owned");
     let mut fields = Vec::new();
+    let mut members = Vec::new();
+    let mut field_types = Vec::new();
     let mut types = Vec::new();
     let mut types_query = Vec::new();
+    let mut types_owned = Vec::new();
+    let mut bundles = Vec::new();
+    let mut into_bundles = Vec::new();
     let mut filters = Vec::new();
     let mut queries = Vec::new();
+    let mut flattened = Vec::new();
+    let mut withs = Vec::new();
+    let mut adapters = TokenStream::new();
     let main_attrs: Vec<_>;
     let mut field_attrs = Vec::<Vec<_>>::new();
     main_attrs = result.attrs.into_iter().filter(is_forwarded).collect();
 
     let crate0 = quote! {::bevy_serde_lens};
 
-    for field in st.fields {
-        let Some(name) = field.ident else {
-            abort!(field.span(), "Tuple struct is not supported.")
+    for (index, field) in st.fields.into_iter().enumerate() {
+        let (name, member) = match field.ident {
+            Some(name) => (name.clone(), name.into_token_stream()),
+            None => (
+                format_ident!("field_{index}"),
+                Index::from(index).into_token_stream(),
+            ),
+        };
+        let orig_ty = field.ty;
+        if is_query {
+            if let Some(extractor) = hierarchy_extractor(&orig_ty) {
+                abort!(
+                    extractor.span(),
+                    "`{}` serializes children and cannot be used in `query` or `parallel` mode.",
+                    extractor
+                )
+            }
+        }
+        let is_flatten = field.attrs.iter().any(|x| parse_attr(x, "flatten"));
+        if is_flatten && is_tuple {
+            abort!(
+                orig_ty.span(),
+                "`flatten` is not supported on tuple structs."
+            )
+        }
+        let with = field.attrs.iter().find_map(parse_attr_with);
+        if is_flatten && with.is_some() {
+            abort!(orig_ty.span(), "`flatten` cannot be used with `with`.")
+        }
+        let ty = match &with {
+            Some(with) => with_adapter(
+                &orig_ty,
+                with,
+                &format_ident!("{name_binding}With{index}"),
+                is_query,
+                &mut adapters,
+            ),
+            None => orig_ty.to_token_stream(),
         };
-        let ty = field.ty;
-        fields.push(name);
+        fields.push(name.clone());
+        members.push(member.clone());
+        flattened.push(is_flatten);
         types.push(quote! {
             <#ty as #crate0::BindProject>::To
         });
         if is_query {
-            types_query.push(quote! {
-                #crate0::BindItem<'t, #ty>
-            })
+            if is_flatten {
+                types_query.push(quote! {
+                    #crate0::FlattenItem<'t, #ty>
+                })
+            } else {
+                types_query.push(quote! {
+                    #crate0::BindItem<'t, #ty>
+                })
+            }
+        }
+        if is_parallel && with.is_some() {
+            types_owned.push(orig_ty.to_token_stream());
+            bundles.push(orig_ty.to_token_stream());
+            into_bundles.push(quote! {owned.#member});
+        } else if is_parallel {
+            types_owned.push(quote! {
+                <#ty as #crate0::OwnedObject>::Owned
+            });
+            bundles.push(quote! {
+                <#ty as #crate0::OwnedObject>::Bundle
+            });
+            into_bundles.push(quote! {
+                <#ty as #crate0::OwnedObject>::into_bundle(owned.#member)
+            });
         }
         if !field.attrs.iter().any(|x| parse_attr(x, "no_filter")) {
             filters.push(quote! {
@@ -175,8 +303,51 @@ fn serialization_archetype2(tokens: TokenStream) -> TokenStream {
             });
         }
         queries.push(quote! {<#ty as #crate0::BindProjectQuery>::Data});
-        field_attrs.push(field.attrs.into_iter().filter(is_forwarded).collect())
+        field_attrs.push(forwarded_attrs(field.attrs, is_flatten));
+        field_types.push(ty);
+        withs.push(with);
+    }
+
+    // Generic parameters only used in projections must be marked as used,
+    // serde bounds are also specified manually since they cannot be inferred from projections.
+    let mut binding_generics = generics.clone();
+    let mut impl_where = generics.clone();
+    let mut ser_bounds = Vec::new();
+    let mut de_bounds = Vec::new();
+    if is_generic {
+        let predicates = &mut binding_generics.make_where_clause().predicates;
+        for ty in &field_types {
+            predicates.push(parse_quote!(#ty: #crate0::BindProject));
+            ser_bounds.push(quote! {<#ty as #crate0::BindProject>::To: #crate0::serde::Serialize});
+            de_bounds
+                .push(quote! {<#ty as #crate0::BindProject>::To: #crate0::serde::Deserialize<'de>});
+        }
+        let predicates = &mut impl_where.make_where_clause().predicates;
+        for ((ty, is_flatten), with) in field_types.iter().zip(&flattened).zip(&withs) {
+            predicates.push(parse_quote!(#ty: #crate0::BindProject));
+            predicates.push(parse_quote!(
+                <#ty as #crate0::BindProject>::To: #crate0::serde::Serialize + #crate0::serde::de::DeserializeOwned
+            ));
+            predicates.push(parse_quote!(
+                <#ty as #crate0::BindProject>::Filter: #crate0::EntityFilter
+            ));
+            if is_query && *is_flatten {
+                predicates.push(parse_quote!(#ty: #crate0::BevyObject));
+            } else if is_query {
+                predicates.push(parse_quote!(#ty: #crate0::BindProjectQuery));
+                if with.is_none() {
+                    predicates.push(parse_quote!(
+                        for<'t> #crate0::BindItem<'t, #ty>: #crate0::serde::Serialize
+                    ));
+                }
+            }
+            if is_parallel && with.is_none() {
+                predicates.push(parse_quote!(#ty: #crate0::OwnedObject));
+            }
+        }
     }
+    let impl_where = impl_where.where_clause;
+    let binding_where = &binding_generics.where_clause;
 
     let filter = roll_tuple(&filters);
 
@@ -189,16 +360,134 @@ fn serialization_archetype2(tokens: TokenStream) -> TokenStream {
 
     if is_query {
         let rolled_fields = roll_tuple(&fields);
+        let mut query_generics = generics.clone();
+        query_generics.params.insert(0, parse_quote!('t));
+        let mut query_attrs = main_attrs.clone();
+        if is_generic {
+            let predicates = &mut query_generics.make_where_clause().predicates;
+            let mut bounds = Vec::new();
+            for ((ty, is_flatten), with) in field_types.iter().zip(&flattened).zip(&withs) {
+                if *is_flatten {
+                    predicates.push(parse_quote!(#ty: #crate0::BevyObject));
+                } else {
+                    predicates.push(parse_quote!(#ty: #crate0::BindProjectQuery));
+                    if with.is_none() {
+                        bounds.push(quote! {#crate0::BindItem<'t, #ty>: #crate0::serde::Serialize});
+                    }
+                }
+            }
+            query_attrs.push(serde_bound(&bounds, &[]));
+        }
+        let mut values: Vec<_> = fields
+            .iter()
+            .zip(&flattened)
+            .map(|(field, is_flatten)| {
+                if *is_flatten {
+                    quote! {#crate0::FlattenItem::new(#field)}
+                } else {
+                    quote! {#field}
+                }
+            })
+            .collect();
+        let mut query_fields = fields.clone();
+        let mut query_field_attrs = field_attrs.clone();
+        for (index, attrs) in query_field_attrs.iter_mut().enumerate() {
+            if withs[index].is_some() {
+                let adapter = format!("{name_binding}With{index}::serialize_ref");
+                attrs.push(parse_quote!(#[serde(serialize_with = #adapter)]));
+            }
+        }
+        if is_generic {
+            push_phantom(
+                &query_generics,
+                is_tuple,
+                &mut query_attrs,
+                &mut query_fields,
+                &mut query_field_attrs,
+                &mut types_query,
+                &mut values,
+            );
+        }
+        let params = &query_generics.params;
+        let query_where = &query_generics.where_clause;
+        let body = struct_body(
+            is_tuple,
+            &query_fields,
+            &query_field_attrs,
+            &types_query,
+            query_where,
+        );
+        let construct = struct_init(is_tuple, &query_fields, &values);
+        let args = generic_args(&generics);
         ext.extend(quote! {
             fn into_ser(query_data: #crate0::Item<'_, Self>) -> impl #crate0::serde::Serialize{
                 let #rolled_fields = query_data;
                 #[derive(#crate0::serde::Serialize)]
-                #(#main_attrs)*
-                struct #name<'t> {
-                    #(#(#field_attrs)* #fields: #types_query,)*
-                }
-                #name {
-                    #(#fields),*
+                #(#query_attrs)*
+                struct #name<#params> #body
+                #name::<'_, #(#args),*> #construct
+            }
+        })
+    }
+
+    let mut owned_impl = TokenStream::new();
+
+    if is_parallel {
+        let bundle = roll_tuple(&bundles);
+        let into_bundle = roll_tuple(&into_bundles);
+        let mut owned_generics = generics.clone();
+        let mut owned_attrs = main_attrs.clone();
+        let mut owned_fields = fields.clone();
+        let mut owned_field_attrs = field_attrs.clone();
+        for (attrs, with) in owned_field_attrs.iter_mut().zip(&withs) {
+            if let Some(with) = with {
+                let with = format!("{}::deserialize", with.to_token_stream());
+                attrs.push(parse_quote!(#[serde(deserialize_with = #with)]));
+            }
+        }
+        if is_generic {
+            let predicates = &mut owned_generics.make_where_clause().predicates;
+            let mut bounds = Vec::new();
+            for (ty, _) in field_types
+                .iter()
+                .zip(&withs)
+                .filter(|(_, with)| with.is_none())
+            {
+                predicates.push(parse_quote!(#ty: #crate0::OwnedObject));
+                bounds.push(
+                    quote! {<#ty as #crate0::OwnedObject>::Owned: #crate0::serde::Deserialize<'de>},
+                );
+            }
+            owned_attrs.push(serde_bound(&[], &bounds));
+            push_phantom(
+                &generics,
+                is_tuple,
+                &mut owned_attrs,
+                &mut owned_fields,
+                &mut owned_field_attrs,
+                &mut types_owned,
+                &mut Vec::<TokenStream>::new(),
+            );
+        }
+        let owned_where = &owned_generics.where_clause;
+        let body = struct_body(
+            is_tuple,
+            &owned_fields,
+            &owned_field_attrs,
+            &types_owned,
+            owned_where,
+        );
+        owned_impl.extend(quote! {
+            #[derive(#crate0::serde::Deserialize)]
+            #(#owned_attrs)*
+            pub struct #name_owned #owned_generics #body
+
+            impl #impl_generics #crate0::OwnedObject for #name #ty_generics #impl_where {
+                type Owned = #name_owned #ty_generics;
+                type Bundle = #bundle;
+
+                fn into_bundle(owned: Self::Owned) -> Self::Bundle {
+                    #into_bundle
                 }
             }
         })
@@ -212,25 +501,398 @@ fn serialization_archetype2(tokens: TokenStream) -> TokenStream {
         })
     }
 
+    let mut binding_attrs = main_attrs.clone();
+    let mut binding_fields = fields.clone();
+    let mut binding_field_attrs = field_attrs.clone();
+    let mut inits: Vec<_> = fields
+        .iter()
+        .map(|_| quote! {#crate0::ZstInit::init()})
+        .collect();
+    if is_generic {
+        binding_attrs.push(serde_bound(&ser_bounds, &de_bounds));
+        push_phantom(
+            &generics,
+            is_tuple,
+            &mut binding_attrs,
+            &mut binding_fields,
+            &mut binding_field_attrs,
+            &mut types,
+            &mut inits,
+        );
+    }
+    let body = struct_body(
+        is_tuple,
+        &binding_fields,
+        &binding_field_attrs,
+        &types,
+        binding_where,
+    );
+    let init = struct_init(is_tuple, &binding_fields, &inits);
+
     quote!(
         const _: () = {
+            #adapters
+
+            #[derive(#crate0::serde::Serialize, #crate0::serde::Deserialize)]
+            #(#binding_attrs)*
+            pub struct #name_binding #generics #body
+
+            impl #impl_generics #crate0::ZstInit for #name_binding #ty_generics #binding_where {
+                fn init() -> Self {
+                    Self #init
+                }
+            }
+
+            impl #impl_generics #crate0::BevyObject for #name #ty_generics #impl_where {
+                const IS_QUERY: bool = #is_query;
+                type Data = #data;
+                type Filter = #filter;
+                type Object = #name_binding #ty_generics;
+
+                fn name() -> &'static str {
+                    #name_str
+                }
+
+                fn schema() -> #crate0::schema::Schema {
+                    #crate0::schema::define(
+                        <Self as #crate0::BevyObject>::name(),
+                        #crate0::schema::trace::<#name_binding #ty_generics>,
+                    )
+                }
+
+                #ext
+            }
+
+            #owned_impl
+        };
+    )
+}
+
+/// Parse `#[bevy_object(with = "path")]`.
+fn parse_attr_with(attr: &Attribute) -> Option<Path> {
+    let Meta::List(list) = &attr.meta else {
+        return None;
+    };
+    if list.path.get_ident().is_none_or(|i| i != "bevy_object") {
+        return None;
+    };
+    let Ok(Meta::NameValue(meta)) = attr.parse_args::<Meta>() else {
+        return None;
+    };
+    if !meta.path.is_ident("with") {
+        return None;
+    }
+    let Expr::Lit(lit) = meta.value else {
+        return None;
+    };
+    let Lit::Str(lit) = lit.lit else { return None };
+    match syn::parse_str(&lit.value()) {
+        Ok(path) => Some(path),
+        Err(_) => abort!(lit.span(), "Expected a path."),
+    }
+}
+
+/// Generate a `MappedSerializer` calling `serialize` and `deserialize` in the `with` module,
+/// returns the field type as `AdaptedComponent`.
+fn with_adapter(
+    ty: &Type,
+    with: &Path,
+    adapter: &Ident,
+    is_query: bool,
+    items: &mut TokenStream,
+) -> TokenStream {
+    let crate0 = quote! {::bevy_serde_lens};
+    items.extend(quote! {
+        pub struct #adapter;
+
+        impl #crate0::MappedSerializer<#ty> for #adapter {
+            fn serialize<S: #crate0::serde::Serializer>(item: &#ty, serializer: S) -> Result<S::Ok, S::Error> {
+                #with::serialize(item, serializer)
+            }
+
+            fn deserialize<'de, D: #crate0::serde::Deserializer<'de>>(deserializer: D) -> Result<#ty, D::Error> {
+                #with::deserialize(deserializer)
+            }
+        }
+    });
+    if is_query {
+        items.extend(quote! {
+            impl #adapter {
+                fn serialize_ref<S: #crate0::serde::Serializer>(item: &&#ty, serializer: S) -> Result<S::Ok, S::Error> {
+                    #with::serialize(*item, serializer)
+                }
+            }
+        });
+    }
+    quote! {#crate0::AdaptedComponent<#ty, #adapter>}
+}
+
+/// Find a hierarchy extractor like `Child` or `ChildVec` in a type, including in generic arguments.
+///
+/// Only unqualified paths and paths under `bevy_serde_lens` are matched.
+fn hierarchy_extractor(ty: &Type) -> Option<&Ident> {
+    match ty {
+        Type::Group(group) => hierarchy_extractor(&group.elem),
+        Type::Paren(paren) => hierarchy_extractor(&paren.elem),
+        Type::Path(path) => {
+            let segments = &path.path.segments;
+            let segment = segments.last()?;
+            let is_lens = path.qself.is_none()
+                && (segments.len() == 1 || segments[0].ident == "bevy_serde_lens");
+            if is_lens
+                && ["Child", "ChildVec", "ChildMap"]
+                    .iter()
+                    .any(|name| segment.ident == name)
+            {
+                return Some(&segment.ident);
+            }
+            let PathArguments::AngleBracketed(args) = &segment.arguments else {
+                return None;
+            };
+            args.args.iter().find_map(|arg| match arg {
+                GenericArgument::Type(ty) => hierarchy_extractor(ty),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Generate `#[serde(bound(serialize = "..", deserialize = ".."))]`.
+fn serde_bound(ser: &[TokenStream], de: &[TokenStream]) -> Attribute {
+    let join = |bounds: &[TokenStream]| {
+        bounds
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let ser = join(ser);
+    let de = join(de);
+    parse_quote!(#[serde(bound(serialize = #ser, deserialize = #de))])
+}
+
+/// Add a skipped `PhantomData` field marking all lifetimes and type parameters as used.
+///
+/// A tuple struct with one field is made `#[serde(transparent)]` to keep serializing as its field.
+fn push_phantom(
+    generics: &Generics,
+    is_tuple: bool,
+    struct_attrs: &mut Vec<Attribute>,
+    fields: &mut Vec<Ident>,
+    attrs: &mut Vec<Vec<Attribute>>,
+    types: &mut Vec<TokenStream>,
+    values: &mut Vec<TokenStream>,
+) {
+    let lifetimes = generics.lifetimes().map(|x| &x.lifetime);
+    let params = generics.type_params().map(|x| &x.ident);
+    if is_tuple && fields.len() == 1 {
+        struct_attrs.push(parse_quote!(#[serde(transparent)]));
+    }
+    fields.push(format_ident!("__phantom"));
+    attrs.push(vec![parse_quote!(#[serde(skip)])]);
+    types.push(quote! {::core::marker::PhantomData<fn() -> (#(&#lifetimes (),)* #(#params,)*)>});
+    values.push(quote! {::core::marker::PhantomData});
+}
+
+/// Arguments of a generic type without bounds, i.e. `'a, T, N`.
+fn generic_args(generics: &Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Type(param) => param.ident.to_token_stream(),
+            GenericParam::Const(param) => param.ident.to_token_stream(),
+        })
+        .collect()
+}
+
+/// Forward `#[serde]` attributes, `#[bevy_object(flatten)]` is forwarded as `#[serde(flatten)]`.
+fn forwarded_attrs(attrs: Vec<Attribute>, is_flatten: bool) -> Vec<Attribute> {
+    let mut result: Vec<_> = attrs.into_iter().filter(is_forwarded).collect();
+    if is_flatten {
+        result.push(parse_quote!(#[serde(flatten)]));
+    }
+    result
+}
+
+/// Body of a struct definition, `where .. { a: A, b: B }` or `(A, B) where ..;`.
+fn struct_body<T: ToTokens>(
+    is_tuple: bool,
+    fields: &[Ident],
+    attrs: &[Vec<Attribute>],
+    types: &[T],
+    where_clause: &Option<WhereClause>,
+) -> TokenStream {
+    if is_tuple {
+        quote! {(#(#(#attrs)* #types,)*) #where_clause;}
+    } else {
+        quote! {#where_clause {#(#(#attrs)* #fields: #types,)*}}
+    }
+}
+
+/// Body of a struct expression, `{ a: a, b: b }` or `(a, b)`.
+fn struct_init<T: ToTokens>(is_tuple: bool, fields: &[Ident], values: &[T]) -> TokenStream {
+    if is_tuple {
+        quote! {(#(#values,)*)}
+    } else {
+        quote! {{#(#fields: #values,)*}}
+    }
+}
+
+fn enum_archetype(name: Ident, attrs: Vec<Attribute>, data: DataEnum) -> TokenStream {
+    let mut name_str = name.to_string();
+    let mut parent = None;
+    let mut is_query = false;
+    let mut is_parallel = false;
+
+    for attr in &attrs {
+        parse_attr_main(
+            attr,
+            &mut is_query,
+            &mut is_parallel,
+            &mut name_str,
+            &mut parent,
+        );
+    }
+
+    if is_query {
+        abort!(name.span(), "Enum does not support `query` or `parallel`.")
+    }
+
+    let crate0 = quote! {::bevy_serde_lens};
+    let name_binding = format_ident!("{name}Binding");
+    let main_attrs: Vec<_> = attrs.into_iter().filter(is_forwarded).collect();
+
+    let mut variants = Vec::new();
+    let mut variant_attrs = Vec::<Vec<_>>::new();
+    let mut variant_bindings = Vec::new();
+    let mut variant_filters = Vec::new();
+    let mut bindings = TokenStream::new();
+
+    for variant in data.variants {
+        let Fields::Named(named) = variant.fields else {
+            abort!(variant.span(), "Only struct variants are supported.")
+        };
+        let variant_name = variant.ident;
+        let variant_binding = format_ident!("{name}{variant_name}Binding");
+        let mut fields = Vec::new();
+        let mut types = Vec::new();
+        let mut filters = Vec::new();
+        let mut field_attrs = Vec::<Vec<_>>::new();
+        for (index, field) in named.named.into_iter().enumerate() {
+            let Some(name) = field.ident else {
+                abort!(field.span(), "Tuple struct is not supported.")
+            };
+            let ty = match field.attrs.iter().find_map(parse_attr_with) {
+                Some(with) => with_adapter(
+                    &field.ty,
+                    &with,
+                    &format_ident!("{variant_binding}With{index}"),
+                    false,
+                    &mut bindings,
+                ),
+                None => field.ty.to_token_stream(),
+            };
+            fields.push(name);
+            types.push(quote! {
+                <#ty as #crate0::BindProject>::To
+            });
+            if !field.attrs.iter().any(|x| parse_attr(x, "no_filter")) {
+                filters.push(quote! {
+                    <#ty as #crate0::BindProject>::Filter
+                });
+            }
+            let is_flatten = field.attrs.iter().any(|x| parse_attr(x, "flatten"));
+            field_attrs.push(forwarded_attrs(field.attrs, is_flatten))
+        }
+        bindings.extend(quote! {
             #[derive(#crate0::serde::Serialize, #crate0::serde::Deserialize)]
-            #(#main_attrs)*
-            pub struct #name_binding {
+            pub struct #variant_binding {
                 #(#(#field_attrs)* #fields: #types,)*
             }
 
-            impl #crate0::ZstInit for #name_binding {
+            impl #crate0::ZstInit for #variant_binding {
                 fn init() -> Self {
                     Self {
                         #(#fields: #crate0::ZstInit::init(),)*
                     }
                 }
             }
+        });
+        variants.push(variant_name);
+        variant_attrs.push(variant.attrs.into_iter().filter(is_forwarded).collect());
+        variant_bindings.push(variant_binding);
+        variant_filters.push(roll_tuple(&filters));
+    }
+
+    let Some(mut filter) = variant_filters.last().cloned() else {
+        abort!(name.span(), "Enum must have at least one variant.")
+    };
+    for variant_filter in variant_filters.iter().rev().skip(1) {
+        filter = quote! {#crate0::Or<(#variant_filter, #filter)>};
+    }
+
+    let no_match = format!("No variant of {name_str} matches the entity.");
+
+    let mut ext = TokenStream::new();
+
+    if let Some(parent) = parent {
+        ext.extend(quote! {
+            fn get_root(world: &mut #crate0::World) -> Option<#crate0::EntityWorldMut> {
+                #parent()
+            }
+        })
+    }
+
+    quote!(
+        const _: () = {
+            #bindings
+
+            pub struct #name_binding;
+
+            impl #crate0::ZstInit for #name_binding {
+                fn init() -> Self {
+                    Self
+                }
+            }
+
+            impl #crate0::serde::Serialize for #name_binding {
+                fn serialize<S: #crate0::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    #[derive(#crate0::serde::Serialize)]
+                    #(#main_attrs)*
+                    enum #name {
+                        #(#(#variant_attrs)* #variants(#variant_bindings),)*
+                    }
+                    let Some(value) = #crate0::SerUtils::with_entity_ref::<S, _>(|entity| {
+                        #(if <#variant_filters as #crate0::EntityFilter>::filter(&entity) {
+                            return Some(#name::#variants(#crate0::ZstInit::init()));
+                        })*
+                        None
+                    })? else {
+                        return Err(#crate0::SerUtils::error::<S>(#no_match));
+                    };
+                    #crate0::serde::Serialize::serialize(&value, serializer)
+                }
+            }
+
+            impl<'de> #crate0::serde::Deserialize<'de> for #name_binding {
+                fn deserialize<D: #crate0::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    #[derive(#crate0::serde::Deserialize)]
+                    #[allow(dead_code)]
+                    #(#main_attrs)*
+                    enum #name {
+                        #(#(#variant_attrs)* #variants(#variant_bindings),)*
+                    }
+                    <#name as #crate0::serde::Deserialize>::deserialize(deserializer)?;
+                    Ok(Self)
+                }
+            }
 
             impl #crate0::BevyObject for #name {
-                const IS_QUERY: bool = #is_query;
-                type Data = #data;
+                const IS_QUERY: bool = false;
+                type Data = ();
                 type Filter = #filter;
                 type Object = #name_binding;
 
@@ -238,9 +900,15 @@ fn serialization_archetype2(tokens: TokenStream) -> TokenStream {
                     #name_str
                 }
 
+                fn schema() -> #crate0::schema::Schema {
+                    #crate0::schema::define(
+                        <Self as #crate0::BevyObject>::name(),
+                        #crate0::schema::trace::<#name_binding>,
+                    )
+                }
+
                 #ext
             }
-
         };
     )
 }
@@ -11,7 +11,10 @@ use std::marker::PhantomData;
 use std::ops::Deref;
 use std::path::PathBuf;
 
-use crate::{MappedSerializer, MappedValue, derrorf, impl_with_notation_newtype, serrorf};
+use crate::{
+    MappedSerializer, MappedValue, derrorf, impl_with_notation_newtype, schema, serrorf,
+    transaction,
+};
 
 scoped_thread_local!(
     pub(crate) static mut SER_REUSABLE_HANDLES: FxHashMap<UntypedAssetId, usize>
@@ -150,6 +153,9 @@ impl<'de, T: Asset, M: MappedSerializer<T>, const P: bool> Deserialize<'de>
 {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         let handle = HandleDeserialization::<T, M>::deserialize(deserializer)?;
+        if schema::is_tracing() {
+            return Ok(SerializeHandle::new(Handle::default()));
+        }
         DeUtils::with_world_mut::<D, _>(|world| {
             Ok(SerializeHandle::new(match handle.index {
                 DeHandleId::Path(path) => {
@@ -164,6 +170,12 @@ impl<'de, T: Asset, M: MappedSerializer<T>, const P: bool> Deserialize<'de>
                             return Err(derrorf!("AssetServer not found."));
                         };
                         let handle = assets.add(value.0);
+                        let asset_id = handle.id();
+                        transaction::record(move |world| {
+                            if let Some(mut assets) = world.get_resource_mut::<Assets<T>>() {
+                                assets.remove(asset_id);
+                            }
+                        });
                         handles.insert(id, handle.clone().untyped());
                         Ok(handle)
                     } else {
@@ -219,6 +231,9 @@ impl<'de, T: Asset> Deserialize<'de> for PathHandle<T> {
         D: serde::Deserializer<'de>,
     {
         let path = PathBuf::deserialize(deserializer)?;
+        if schema::is_tracing() {
+            return Ok(PathHandle(Handle::default()));
+        }
         DeUtils::with_world_mut::<D, _>(|world| {
             let Some(asset_server) = world.get_resource::<AssetServer>() else {
                 return Err(serde::de::Error::custom("AssetServer not found."));
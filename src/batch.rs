@@ -1,3 +1,8 @@
+use crate::error::{self, PathSegment};
+use crate::filter;
+use crate::replication;
+use crate::schema::{self, Schema};
+use crate::value::Value;
 use crate::{BevyObject, SerializeNonSend, SerializeResource, ZstInit, root::Root};
 use bevy::ecs::{entity::Entity, resource::Resource, world::World};
 use bevy::reflect::TypePath;
@@ -19,6 +24,22 @@ pub trait BatchSerialization {
     fn deserialize_map<'de, M>(name: &str, map: &mut M) -> Result<(), M::Error>
     where
         M: MapAccess<'de>;
+    /// Layout of the serialized batch, default is [`Schema::Any`].
+    fn schema() -> Schema {
+        Schema::Any
+    }
+    /// Add entries of this batch to the layout of a map.
+    #[allow(unused_variables)]
+    fn schema_map(properties: &mut Vec<(String, Schema)>) {}
+    /// Call `f` on the name and value of each item in a parsed save,
+    /// names can be modified in place.
+    #[allow(unused_variables)]
+    fn migrate(
+        value: &mut Value,
+        f: &mut dyn FnMut(&mut String, &mut Value) -> Result<(), String>,
+    ) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 /// A Single item in [`BatchSerialization`].
@@ -27,6 +48,10 @@ pub trait SerializeWorld {
     fn name() -> &'static str;
     fn serialize<S: Serializer>(world: &mut World, s: S) -> Result<S::Ok, S::Error>;
     fn despawn(world: &mut World);
+    /// Layout of the serialized item, default is [`Schema::Any`].
+    fn schema() -> Schema {
+        Schema::Any
+    }
 }
 
 impl<T> BatchSerialization for T
@@ -68,6 +93,21 @@ where
             )))
         }
     }
+
+    fn schema() -> Schema {
+        <T as SerializeWorld>::schema()
+    }
+
+    fn schema_map(properties: &mut Vec<(String, Schema)>) {
+        properties.push((Self::name().to_owned(), <T as SerializeWorld>::schema()));
+    }
+
+    fn migrate(
+        value: &mut Value,
+        f: &mut dyn FnMut(&mut String, &mut Value) -> Result<(), String>,
+    ) -> Result<(), String> {
+        f(&mut Self::name().to_owned(), value)
+    }
 }
 
 pub(crate) struct SerializeWorldLens<'t, S: SerializeWorld> {
@@ -95,7 +135,20 @@ where
     }
 
     fn serialize<S: Serializer>(world: &mut World, serializer: S) -> Result<S::Ok, S::Error> {
-        if T::IS_QUERY {
+        if replication::is_snapshot() {
+            return replication::serialize_root::<T, S>(world, serializer);
+        }
+        if T::IS_QUERY && filter::is_filtered() {
+            let mut query = world.query_filtered::<(Entity, T::Data), T::Filter>();
+            ScopeUtils::serialize_scope(world, || {
+                let items = query
+                    .iter(world)
+                    .filter(|(entity, _)| filter::is_selected(*entity))
+                    .map(|(_, data)| data)
+                    .collect::<Vec<_>>();
+                serializer.collect_seq(items.into_iter().map(T::into_ser))
+            })
+        } else if T::IS_QUERY {
             let mut query = world.query_filtered::<T::Data, T::Filter>();
             ScopeUtils::serialize_scope(world, || {
                 serializer.collect_seq(query.iter(world).map(T::into_ser))
@@ -103,8 +156,12 @@ where
         } else {
             use serde::ser::SerializeSeq;
             let mut query = world.query_filtered::<Entity, T::Filter>();
-            let mut seq = serializer.serialize_seq(Some(query.iter(world).count()))?;
-            for entity in query.iter(world) {
+            let entities = query
+                .iter(world)
+                .filter(|entity| filter::is_selected(*entity))
+                .collect::<Vec<_>>();
+            let mut seq = serializer.serialize_seq(Some(entities.len()))?;
+            for entity in entities {
                 ScopeUtils::serialize_scope(world, || {
                     ScopeUtils::current_entity_scope(entity, || seq.serialize_element(&T::init()))
                 })?;
@@ -115,11 +172,18 @@ where
 
     fn despawn(world: &mut World) {
         let mut query = world.query_filtered::<Entity, T::Filter>();
-        let queue = query.iter(world).collect::<Vec<_>>();
+        let queue = query
+            .iter(world)
+            .filter(|entity| filter::is_selected(*entity))
+            .collect::<Vec<_>>();
         for entity in queue {
             let _ = world.despawn(entity);
         }
     }
+
+    fn schema() -> Schema {
+        Schema::Array(Box::new(T::schema()))
+    }
 }
 
 impl<T> SerializeWorld for SerializeResource<T>
@@ -136,8 +200,16 @@ where
     }
 
     fn despawn(world: &mut World) {
+        // Not bound to entities.
+        if filter::is_filtered() {
+            return;
+        }
         world.remove_resource::<T>();
     }
+
+    fn schema() -> Schema {
+        schema::trace::<T>()
+    }
 }
 
 impl<T> SerializeWorld for SerializeNonSend<T>
@@ -154,8 +226,16 @@ where
     }
 
     fn despawn(world: &mut World) {
+        // Not bound to entities.
+        if filter::is_filtered() {
+            return;
+        }
         world.remove_non_send::<T>();
     }
+
+    fn schema() -> Schema {
+        schema::trace::<T>()
+    }
 }
 
 /// Join two [`BatchSerialization`] types.
@@ -208,6 +288,32 @@ where
         }
         Ok(())
     }
+
+    fn schema() -> Schema {
+        let mut properties = Vec::with_capacity(Self::LEN);
+        Self::schema_map(&mut properties);
+        Schema::Object(properties)
+    }
+
+    fn schema_map(properties: &mut Vec<(String, Schema)>) {
+        properties.push((A::name().to_owned(), A::schema()));
+        B::schema_map(properties);
+    }
+
+    fn migrate(
+        value: &mut Value,
+        f: &mut dyn FnMut(&mut String, &mut Value) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let Some(entries) = value.as_map_mut() else {
+            return Ok(());
+        };
+        for (key, value) in entries {
+            if let Value::String(name) = key {
+                f(name, value)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<'de, A, B> Deserialize<'de> for Join<A, B>
@@ -239,11 +345,17 @@ where
         M: MapAccess<'de>,
     {
         while let Some(key) = map.next_key::<Cow<str>>()? {
-            if key.as_ref() == A::name() {
-                map.next_value::<A::De>()?;
-            } else {
-                B::deserialize_map(key.as_ref(), &mut map)?;
-            }
+            error::path_scope(
+                || PathSegment::Key(key.to_string()),
+                || {
+                    if key.as_ref() == A::name() {
+                        map.next_value::<A::De>()?;
+                    } else {
+                        B::deserialize_map(key.as_ref(), &mut map)?;
+                    }
+                    Ok(())
+                },
+            )?;
             //Self::deserialize_map(&key, &mut map)?
         }
         Ok(Join(PhantomData))
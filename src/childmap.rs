@@ -10,6 +10,9 @@ use std::{
     marker::PhantomData,
 };
 
+use crate::error::{self, PathSegment};
+use crate::lenient::Lenient;
+use crate::schema::{self, Schema};
 use crate::{BevyObject, BindProject, ZstInit, root::RootObject};
 
 /// Types that references one or many entities with a serializable key.
@@ -92,6 +95,9 @@ impl<'de, T: BevyObject, C: ChildMapLike> Deserialize<'de> for ChildMap<T, C> {
     where
         D: serde::Deserializer<'de>,
     {
+        if let Some(this) = schema::provided(|| Schema::Map(Box::new(T::schema()))) {
+            return Ok(this);
+        }
         deserializer.deserialize_seq(ChildMap::<T, C>(PhantomData))
     }
 }
@@ -107,11 +113,20 @@ impl<'de, T: BevyObject, C: ChildMapLike> Visitor<'de> for ChildMap<T, C> {
     where
         A: MapAccess<'de>,
     {
-        while let Some((key, child)) = seq.next_entry::<C::Key, RootObject<T>>()? {
+        while let Some(key) = seq.next_key::<C::Key>()? {
+            let Lenient(child) = error::path_scope(
+                || PathSegment::key(&key),
+                || seq.next_value::<Lenient<RootObject<T>>>(),
+            )?;
+            // Skipped in lenient mode.
+            let Some(child) = child else {
+                continue;
+            };
             DeUtils::with_entity_mut_err::<A::Error, _>(|parent| {
                 C::add_child(parent, key, child.get()).map_err(serde::de::Error::custom)
             })??;
         }
+        crate::merge::track(crate::merge::move_component::<C>);
         Ok(ChildMap(PhantomData))
     }
 }
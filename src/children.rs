@@ -9,7 +9,10 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Debug, Display};
 use std::{any::type_name, marker::PhantomData};
 
+use crate::error::{self, PathSegment};
+use crate::lenient::Lenient;
 use crate::root::RootObject;
+use crate::schema;
 use crate::{BevyObject, BindProject, Maybe, ZstInit};
 
 /// Types that references one or many entities similar to [`Children`].
@@ -95,10 +98,18 @@ impl<T: BevyObject, C: ChildrenLike> Serialize for Child<T, C> {
 
 impl<'de, T: BevyObject, C: ChildrenLike> Deserialize<'de> for Child<T, C> {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if let Some(this) = schema::provided(T::schema) {
+            return Ok(this);
+        }
         let child = RootObject::<T>::deserialize(deserializer)?.get();
         DeUtils::with_entity_mut::<D, _>(|entity| {
             C::add_child(entity, child).map_err(serde::de::Error::custom)
         })??;
+        crate::merge::track(move |world, _, to| {
+            if let Ok(parent) = world.get_entity_mut(to) {
+                let _ = C::add_child(parent, child);
+            }
+        });
         Ok(Child(PhantomData))
     }
 }
@@ -224,7 +235,15 @@ impl<'de, T: BevyObject, C: ChildrenLike> Visitor<'de> for ChildVec<T, C> {
     }
 
     fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
-        while seq.next_element::<Child<T, C>>()?.is_some() {}
+        let mut index = 0;
+        while error::path_scope(
+            || PathSegment::Index(index),
+            || seq.next_element::<Lenient<Child<T, C>>>(),
+        )?
+        .is_some()
+        {
+            index += 1;
+        }
         Ok(ChildVec(PhantomData))
     }
 }
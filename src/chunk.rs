@@ -0,0 +1,142 @@
+//! Module for saving and unloading a world in spatial chunks.
+//!
+//! Root objects are grouped into chunks by a key extractor on [`EntityRef`],
+//! each chunk is saved as a separate document and can be loaded or despawned on its own.
+//!
+//! ```
+//! fn chunk_of(entity: &EntityRef) -> Option<IVec2> {
+//!     let position = entity.get::<Transform>()?.translation.truncate();
+//!     Some((position / CHUNK_SIZE).floor().as_ivec2())
+//! }
+//!
+//! // Save and unload a chunk.
+//! let bytes = world.save_chunk::<SaveFile, _>(&chunk, chunk_of, Postcard)?;
+//! world.despawn_chunk::<SaveFile, _>(&chunk, chunk_of);
+//! // Stream it back in.
+//! world.load_chunk::<SaveFile>(&bytes, Postcard)?;
+//! ```
+//!
+//! # Note
+//!
+//! * Only root objects are keyed, children are saved and despawned with their roots.
+//! * Resources are saved in every chunk and not removed by `despawn_chunk`,
+//!   batches saved as chunks should usually not contain resources.
+use std::hash::Hash;
+
+use bevy::ecs::entity::Entity;
+use bevy::ecs::world::{EntityRef, World};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::error::LoadError;
+use crate::filter;
+use crate::format::SaveFormat;
+use crate::save_file::SaveFileError;
+use crate::{BatchSerialization, WorldExtension};
+
+fn group_chunks<K: Eq + Hash>(
+    world: &mut World,
+    key: impl Fn(&EntityRef) -> Option<K>,
+) -> FxHashMap<K, FxHashSet<Entity>> {
+    let mut chunks = FxHashMap::<K, FxHashSet<Entity>>::default();
+    let mut query = world.query::<EntityRef>();
+    for entity in query.iter(world) {
+        if let Some(chunk) = key(&entity) {
+            chunks.entry(chunk).or_default().insert(entity.id());
+        }
+    }
+    chunks
+}
+
+fn chunk_entities<K: Eq>(
+    world: &mut World,
+    chunk: &K,
+    key: impl Fn(&EntityRef) -> Option<K>,
+) -> FxHashSet<Entity> {
+    let mut query = world.query::<EntityRef>();
+    query
+        .iter(world)
+        .filter(|entity| key(entity).as_ref() == Some(chunk))
+        .map(|entity| entity.id())
+        .collect()
+}
+
+/// Extension methods on [`World`] for chunk saving.
+pub trait ChunkExtension {
+    /// Save a [`BatchSerialization`] type as one document per chunk.
+    ///
+    /// A document is created for every chunk that contains an entity with a key.
+    fn save_chunks<T: BatchSerialization, K: Eq + Hash>(
+        &mut self,
+        key: impl Fn(&EntityRef) -> Option<K>,
+        format: impl SaveFormat,
+    ) -> Result<FxHashMap<K, Vec<u8>>, SaveFileError>;
+
+    /// Save root objects of a [`BatchSerialization`] type in a single chunk.
+    fn save_chunk<T: BatchSerialization, K: Eq>(
+        &mut self,
+        chunk: &K,
+        key: impl Fn(&EntityRef) -> Option<K>,
+        format: impl SaveFormat,
+    ) -> Result<Vec<u8>, SaveFileError>;
+
+    /// Load a chunk saved by `save_chunk` or `save_chunks`, only spawns root objects in the chunk.
+    fn load_chunk<T: BatchSerialization>(
+        &mut self,
+        bytes: &[u8],
+        format: impl SaveFormat,
+    ) -> Result<(), LoadError>;
+
+    /// Despawn root objects of a [`BatchSerialization`] type in a single chunk,
+    /// like `despawn_bound_objects`.
+    fn despawn_chunk<T: BatchSerialization, K: Eq>(
+        &mut self,
+        chunk: &K,
+        key: impl Fn(&EntityRef) -> Option<K>,
+    );
+}
+
+impl ChunkExtension for World {
+    fn save_chunks<T: BatchSerialization, K: Eq + Hash>(
+        &mut self,
+        key: impl Fn(&EntityRef) -> Option<K>,
+        format: impl SaveFormat,
+    ) -> Result<FxHashMap<K, Vec<u8>>, SaveFileError> {
+        group_chunks(self, key)
+            .into_iter()
+            .map(|(chunk, entities)| {
+                let bytes = filter::filter_roots(&entities, || {
+                    format.serialize(&self.serialize_lens::<T>())
+                })
+                .map_err(SaveFileError::Format)?;
+                Ok((chunk, bytes))
+            })
+            .collect()
+    }
+
+    fn save_chunk<T: BatchSerialization, K: Eq>(
+        &mut self,
+        chunk: &K,
+        key: impl Fn(&EntityRef) -> Option<K>,
+        format: impl SaveFormat,
+    ) -> Result<Vec<u8>, SaveFileError> {
+        let entities = chunk_entities(self, chunk, key);
+        filter::filter_roots(&entities, || self.save_to_bytes::<T>(format))
+    }
+
+    fn load_chunk<T: BatchSerialization>(
+        &mut self,
+        bytes: &[u8],
+        format: impl SaveFormat,
+    ) -> Result<(), LoadError> {
+        self.load_from_bytes::<T>(bytes, format)
+    }
+
+    fn despawn_chunk<T: BatchSerialization, K: Eq>(
+        &mut self,
+        chunk: &K,
+        key: impl Fn(&EntityRef) -> Option<K>,
+    ) {
+        let entities = chunk_entities(self, chunk, key);
+        filter::filter_roots(&entities, || self.despawn_bound_objects::<T>())
+    }
+}
@@ -0,0 +1,147 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bevy::ecs::event::Event;
+use bevy::ecs::system::Commands;
+use bevy::ecs::world::World;
+
+use crate::error::LoadError;
+use crate::format::{SaveFormat, write_atomic};
+use crate::save_file::SaveFileError;
+use crate::{BatchSerialization, WorldExtension};
+
+/// Destination of [`CommandsExtension::save`].
+pub enum SaveTarget {
+    /// Write to a file atomically, replacing its content.
+    Path(PathBuf),
+    /// Write to a [`Write`] implementor.
+    Writer(Box<dyn Write + Send + Sync>),
+}
+
+impl SaveTarget {
+    /// Create a [`SaveTarget`] from a [`Write`] implementor.
+    pub fn writer(writer: impl Write + Send + Sync + 'static) -> Self {
+        SaveTarget::Writer(Box::new(writer))
+    }
+
+    fn write(self, bytes: &[u8]) -> Result<(), SaveFileError> {
+        match self {
+            SaveTarget::Path(path) => write_atomic(&path, bytes)?,
+            SaveTarget::Writer(mut writer) => {
+                writer.write_all(bytes)?;
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<PathBuf> for SaveTarget {
+    fn from(value: PathBuf) -> Self {
+        SaveTarget::Path(value)
+    }
+}
+
+impl From<&Path> for SaveTarget {
+    fn from(value: &Path) -> Self {
+        SaveTarget::Path(value.to_owned())
+    }
+}
+
+impl From<&str> for SaveTarget {
+    fn from(value: &str) -> Self {
+        SaveTarget::Path(value.into())
+    }
+}
+
+impl From<String> for SaveTarget {
+    fn from(value: String) -> Self {
+        SaveTarget::Path(value.into())
+    }
+}
+
+/// Identifies a request queued by [`CommandsExtension`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestId(u64);
+
+impl RequestId {
+    fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        RequestId(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Triggered when a [`CommandsExtension::save`] command completes or fails.
+#[derive(Debug, Event)]
+pub struct SaveCompleted {
+    /// Id returned by [`CommandsExtension::save`].
+    pub id: RequestId,
+    /// Path of the [`SaveTarget`], if any.
+    pub path: Option<PathBuf>,
+    pub result: Result<(), SaveFileError>,
+}
+
+/// Triggered when a [`CommandsExtension::load`] command completes or fails.
+#[derive(Debug, Event)]
+pub struct LoadCompleted {
+    /// Id returned by [`CommandsExtension::load`].
+    pub id: RequestId,
+    pub result: Result<(), LoadError>,
+}
+
+/// Extension methods on [`Commands`].
+///
+/// Results are reported by triggering [`SaveCompleted`] or [`LoadCompleted`]
+/// with the [`RequestId`] returned by the command.
+pub trait CommandsExtension {
+    /// Queue saving a [`BatchSerialization`] type to a file or a writer in a [`SaveFormat`].
+    fn save<T: BatchSerialization + 'static, F: SaveFormat>(
+        &mut self,
+        target: impl Into<SaveTarget>,
+        format: F,
+    ) -> RequestId;
+
+    /// Queue loading a [`BatchSerialization`] type from bytes in a [`SaveFormat`].
+    fn load<T: BatchSerialization + 'static, F: SaveFormat>(
+        &mut self,
+        bytes: impl Into<Vec<u8>>,
+        format: F,
+    ) -> RequestId;
+}
+
+impl CommandsExtension for Commands<'_, '_> {
+    fn save<T: BatchSerialization + 'static, F: SaveFormat>(
+        &mut self,
+        target: impl Into<SaveTarget>,
+        format: F,
+    ) -> RequestId {
+        let id = RequestId::next();
+        let target = target.into();
+        let path = match &target {
+            SaveTarget::Path(path) => Some(path.clone()),
+            SaveTarget::Writer(_) => None,
+        };
+        self.queue(move |world: &mut World| {
+            let result = world
+                .save_to_bytes::<T>(format)
+                .and_then(|bytes| target.write(&bytes));
+            world.trigger(SaveCompleted { id, path, result });
+        });
+        id
+    }
+
+    fn load<T: BatchSerialization + 'static, F: SaveFormat>(
+        &mut self,
+        bytes: impl Into<Vec<u8>>,
+        format: F,
+    ) -> RequestId {
+        let id = RequestId::next();
+        let bytes = bytes.into();
+        self.queue(move |world: &mut World| {
+            let result = world.load_from_bytes::<T>(&bytes, format);
+            world.trigger(LoadCompleted { id, result });
+        });
+        id
+    }
+}
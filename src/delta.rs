@@ -0,0 +1,296 @@
+//! Module for delta saves.
+//!
+//! A delta save contains only objects of a [`BevyObject`] that changed since a
+//! [`DeltaBaseline`], objects are identified by a key component `K`, like in `load_merge`.
+//!
+//! ```
+//! // After a full save or load.
+//! let baseline = world.delta_baseline::<Unit, Id>();
+//! // Later, save changes since the baseline.
+//! world.save_delta::<Unit, Id, _>(&baseline, serializer)?;
+//! // Apply the delta on top of the full save.
+//! world.load::<Unit, _>(snapshot)?;
+//! world.load_delta::<Unit, Id, _>(delta)?;
+//! ```
+//!
+//! The delta is a map:
+//!
+//! ```json
+//! {
+//!     "spawned": [{"id": 4, "hp": 40}],
+//!     "changed": [{"id": 1, "hp": 5}],
+//!     "despawned": [3]
+//! }
+//! ```
+//!
+//! # Note
+//!
+//! * An object is changed if a component of `T` on its entity is added or mutated
+//!   since the baseline, removed components are not detected.
+//!   In `query` mode, components of `T` are the components in `T::Data` and required by `T::Filter`.
+//! * If `T` is not in `query` mode, any component on its entity and descendants is checked.
+//! * `K` must be a plain component of `T`.
+use std::cell::RefCell;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use bevy::ecs::change_detection::Tick;
+use bevy::ecs::component::{Component, ComponentId};
+use bevy::ecs::entity::Entity;
+use bevy::ecs::hierarchy::Children;
+use bevy::ecs::world::World;
+use bevy_serde_lens_core::ScopeUtils;
+use rustc_hash::FxHashSet;
+use serde::de::{DeserializeOwned, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::BevyObject;
+use crate::error::{self, PathSegment};
+use crate::root::Root;
+
+#[allow(unused)]
+use crate::WorldExtension;
+
+const FIELDS: &[&str] = &["spawned", "changed", "despawned"];
+
+/// Keys and change tick of a [`BevyObject`] recorded by [`WorldExtension::delta_baseline`].
+#[derive(Debug, Clone)]
+pub struct DeltaBaseline<K> {
+    tick: Tick,
+    keys: FxHashSet<K>,
+}
+
+impl<K: Eq + Hash> DeltaBaseline<K> {
+    /// Change tick of the baseline.
+    pub fn tick(&self) -> Tick {
+        self.tick
+    }
+
+    /// Returns true if an object with key `key` existed at the baseline.
+    pub fn contains(&self, key: &K) -> bool {
+        self.keys.contains(key)
+    }
+}
+
+pub(crate) fn baseline<T: BevyObject, K: Component + Eq + Hash + Clone>(
+    world: &mut World,
+) -> DeltaBaseline<K> {
+    let mut query = world.query_filtered::<&K, T::Filter>();
+    let keys = query.iter(world).cloned().collect();
+    // Changes after this point have a newer tick than the baseline.
+    let tick = world.increment_change_tick();
+    DeltaBaseline { tick, keys }
+}
+
+/// Components of `T` checked for changes, `None` if every component on the entity is checked.
+fn tracked_components<T: BevyObject>(world: &mut World) -> Option<FxHashSet<ComponentId>> {
+    // `T::Data` is empty if not in `query` mode, so `Maybe` and `no_filter` fields are unknown.
+    if !T::IS_QUERY {
+        return None;
+    }
+    let query = world.query_filtered::<T::Data, T::Filter>();
+    let access = query.component_access();
+    let reads = access.access().try_reads_and_writes().ok()?;
+    Some(reads.iter().chain(access.with_filters()).collect())
+}
+
+fn is_changed<T: BevyObject>(
+    world: &World,
+    entity: Entity,
+    tracked: Option<&FxHashSet<ComponentId>>,
+    since: Tick,
+    now: Tick,
+) -> bool {
+    let Ok(entity_ref) = world.get_entity(entity) else {
+        return false;
+    };
+    let changed = |id: ComponentId| {
+        entity_ref
+            .get_change_ticks_by_id(id)
+            .is_some_and(|ticks| ticks.is_changed(since, now))
+    };
+    let changed = match tracked {
+        Some(tracked) => tracked.iter().copied().any(changed),
+        None => entity_ref.archetype().iter_components().any(changed),
+    };
+    changed || (!T::IS_QUERY && is_descendant_changed(world, entity, since, now))
+}
+
+fn is_descendant_changed(world: &World, entity: Entity, since: Tick, now: Tick) -> bool {
+    let Some(children) = world.get::<Children>(entity) else {
+        return false;
+    };
+    children.iter().any(|child| {
+        world.get_entity(*child).is_ok_and(|child_ref| {
+            child_ref.archetype().iter_components().any(|id| {
+                child_ref
+                    .get_change_ticks_by_id(id)
+                    .is_some_and(|ticks| ticks.is_changed(since, now))
+            })
+        }) || is_descendant_changed(world, *child, since, now)
+    })
+}
+
+/// Serializes objects that changed since a [`DeltaBaseline`].
+pub(crate) struct DeltaLens<'t, T, K> {
+    world: RefCell<&'t mut World>,
+    spawned: Vec<Entity>,
+    changed: Vec<Entity>,
+    despawned: Vec<K>,
+    p: PhantomData<T>,
+}
+
+impl<'t, T: BevyObject, K: Component + Eq + Hash + Clone> DeltaLens<'t, T, K> {
+    pub(crate) fn new(world: &'t mut World, baseline: &DeltaBaseline<K>) -> Self {
+        let now = world.change_tick();
+        let tracked = tracked_components::<T>(world);
+        let mut query = world.query_filtered::<(Entity, &K), T::Filter>();
+        let mut spawned = Vec::new();
+        let mut changed = Vec::new();
+        let mut keys = FxHashSet::default();
+        for (entity, key) in query.iter(world) {
+            if !baseline.keys.contains(key) {
+                spawned.push(entity);
+            } else if is_changed::<T>(world, entity, tracked.as_ref(), baseline.tick, now) {
+                changed.push(entity);
+            }
+            keys.insert(key.clone());
+        }
+        let despawned = baseline
+            .keys
+            .iter()
+            .filter(|key| !keys.contains(key))
+            .cloned()
+            .collect();
+        DeltaLens {
+            world: RefCell::new(world),
+            spawned,
+            changed,
+            despawned,
+            p: PhantomData,
+        }
+    }
+}
+
+struct EntitiesLens<'a, 't, T> {
+    world: &'a RefCell<&'t mut World>,
+    entities: &'a [Entity],
+    p: PhantomData<T>,
+}
+
+impl<T: BevyObject> Serialize for EntitiesLens<'_, '_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        let mut world = self.world.borrow_mut();
+        let world: &mut World = &mut world;
+        if T::IS_QUERY {
+            let mut query = world.query_filtered::<T::Data, T::Filter>();
+            let query = query.query(world);
+            ScopeUtils::serialize_scope(world, || {
+                serializer.collect_seq(
+                    self.entities
+                        .iter()
+                        .filter_map(|entity| query.get(*entity).ok())
+                        .map(T::into_ser),
+                )
+            })
+        } else {
+            let mut seq = serializer.serialize_seq(Some(self.entities.len()))?;
+            for entity in self.entities {
+                ScopeUtils::serialize_scope(world, || {
+                    ScopeUtils::current_entity_scope(*entity, || seq.serialize_element(&T::init()))
+                })?;
+            }
+            seq.end()
+        }
+    }
+}
+
+impl<T: BevyObject, K: Serialize> Serialize for DeltaLens<'_, T, K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Delta", 3)?;
+        s.serialize_field(
+            "spawned",
+            &EntitiesLens::<T> {
+                world: &self.world,
+                entities: &self.spawned,
+                p: PhantomData,
+            },
+        )?;
+        s.serialize_field(
+            "changed",
+            &EntitiesLens::<T> {
+                world: &self.world,
+                entities: &self.changed,
+                p: PhantomData,
+            },
+        )?;
+        s.serialize_field("despawned", &self.despawned)?;
+        s.end()
+    }
+}
+
+/// Deserializes a delta save, returns keys of despawned objects.
+pub(crate) struct Delta<T, K> {
+    pub(crate) despawned: Vec<K>,
+    p: PhantomData<T>,
+}
+
+impl<'de, T: BevyObject, K: DeserializeOwned> Deserialize<'de> for Delta<T, K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Delta", FIELDS, DeltaVisitor::<T, K>(PhantomData))
+    }
+}
+
+struct DeltaVisitor<T, K>(PhantomData<(T, K)>);
+
+impl<'de, T: BevyObject, K: DeserializeOwned> Visitor<'de> for DeltaVisitor<T, K> {
+    type Value = Delta<T, K>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a delta save")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        for (index, field) in FIELDS[..2].iter().enumerate() {
+            error::path_scope(
+                || PathSegment::Key((*field).to_owned()),
+                || seq.next_element::<Root<T>>(),
+            )?
+            .ok_or_else(|| serde::de::Error::invalid_length(index, &self))?;
+        }
+        let despawned = error::path_scope(
+            || PathSegment::Key(FIELDS[2].to_owned()),
+            || seq.next_element(),
+        )?
+        .ok_or_else(|| serde::de::Error::invalid_length(2, &self))?;
+        Ok(Delta {
+            despawned,
+            p: PhantomData,
+        })
+    }
+
+    fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+        let mut despawned = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            error::path_scope(
+                || PathSegment::Key(key.clone()),
+                || {
+                    match key.as_str() {
+                        "spawned" | "changed" => {
+                            map.next_value::<Root<T>>()?;
+                        }
+                        "despawned" => despawned = map.next_value()?,
+                        _ => return Err(serde::de::Error::unknown_field(&key, FIELDS)),
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+        Ok(Delta {
+            despawned,
+            p: PhantomData,
+        })
+    }
+}
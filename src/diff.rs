@@ -0,0 +1,276 @@
+//! Module for comparing saves.
+//!
+//! [`diff`] compares two saves of the same [`BatchSerialization`] type as [`Value`]s,
+//! walking batches, roots, [`BevyObject`]s and their children using the layout from [`schema`],
+//! so no reflection is needed.
+//!
+//! ```
+//! let report = world.diff::<SaveFile>(&mut replay_world)?;
+//! for entry in report.iter() {
+//!     println!("{entry}");
+//! }
+//! ```
+//!
+//! Prints:
+//!
+//! ```text
+//! ~ Unit[1].hp: 20 -> 15
+//! + Unit[2]: {"hp": 40}
+//! - Building[0]: {"hp": 100}
+//! ```
+//!
+//! # Note
+//!
+//! * Objects in `Root` and [`ChildVec`](crate::ChildVec) are matched by index,
+//!   objects in [`ChildMap`](crate::ChildMap) are matched by key.
+//! * Components are compared as a whole, the path of a difference ends at its field name.
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+
+use crate::BatchSerialization;
+use crate::error::{ErrorPath, PathSegment};
+use crate::schema::{self, Schema};
+use crate::value::Value;
+
+#[allow(unused)]
+use crate::{BevyObject, WorldExtension};
+
+/// Kind of a [`DiffEntry`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    /// Only present in the new save.
+    Added(Value),
+    /// Only present in the old save.
+    Removed(Value),
+    /// Present in both saves with different values.
+    Changed { old: Value, new: Value },
+}
+
+/// A difference between two saves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    pub path: ErrorPath,
+    pub difference: Difference,
+}
+
+impl Display for DiffEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.difference {
+            Difference::Added(value) => write!(f, "+ {}: {}", self.path, Compact(value)),
+            Difference::Removed(value) => write!(f, "- {}: {}", self.path, Compact(value)),
+            Difference::Changed { old, new } => {
+                write!(f, "~ {}: {} -> {}", self.path, Compact(old), Compact(new))
+            }
+        }
+    }
+}
+
+/// Report of [`diff`], in save order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorldDiff(pub Vec<DiffEntry>);
+
+impl WorldDiff {
+    /// Returns true if the saves are equal.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DiffEntry> {
+        self.0.iter()
+    }
+}
+
+impl Display for WorldDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for entry in &self.0 {
+            writeln!(f, "{entry}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare two saves of a [`BatchSerialization`] type.
+pub fn diff<T: BatchSerialization>(old: &Value, new: &Value) -> WorldDiff {
+    let root = schema::root_schema::<T>();
+    let mut differ = Differ {
+        definitions: &root.definitions,
+        path: Vec::new(),
+        entries: Vec::new(),
+    };
+    match &root.schema {
+        Schema::Object(items) if T::LEN > 1 => differ.object(items, old, new, Differ::item),
+        schema => differ.item(schema, old, new),
+    }
+    WorldDiff(differ.entries)
+}
+
+struct Differ<'t> {
+    definitions: &'t BTreeMap<String, Schema>,
+    path: Vec<PathSegment>,
+    entries: Vec<DiffEntry>,
+}
+
+impl<'t> Differ<'t> {
+    fn push(&mut self, difference: Difference) {
+        self.entries.push(DiffEntry {
+            path: ErrorPath(self.path.clone()),
+            difference,
+        })
+    }
+
+    fn scope(&mut self, segment: PathSegment, f: impl FnOnce(&mut Self)) {
+        self.path.push(segment);
+        f(self);
+        self.path.pop();
+    }
+
+    /// Fields of a [`BevyObject`].
+    fn fields(&self, schema: &Schema) -> Option<&'t [(String, Schema)]> {
+        match schema {
+            Schema::Ref(name) => match self.definitions.get(name) {
+                Some(Schema::Object(fields)) => Some(fields),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn leaf(&mut self, old: &Value, new: &Value) {
+        if old != new {
+            self.push(Difference::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            })
+        }
+    }
+
+    /// An item in a batch, sequences are roots.
+    fn item(&mut self, schema: &Schema, old: &Value, new: &Value) {
+        match schema {
+            Schema::Array(inner) => self.seq(inner, old, new),
+            schema => self.node(schema, old, new),
+        }
+    }
+
+    fn node(&mut self, schema: &Schema, old: &Value, new: &Value) {
+        if let Some(fields) = self.fields(schema) {
+            return self.object(fields, old, new, Self::node);
+        }
+        match schema {
+            Schema::Array(inner) if self.fields(inner).is_some() => self.seq(inner, old, new),
+            Schema::Map(inner) if self.fields(inner).is_some() => self.map(inner, old, new),
+            _ => self.leaf(old, new),
+        }
+    }
+
+    fn seq(&mut self, inner: &Schema, old: &Value, new: &Value) {
+        let (Some(old), Some(new)) = (old.as_seq(), new.as_seq()) else {
+            return self.leaf(old, new);
+        };
+        for index in 0..old.len().max(new.len()) {
+            self.scope(PathSegment::Index(index), |this| {
+                match (old.get(index), new.get(index)) {
+                    (Some(old), Some(new)) => this.node(inner, old, new),
+                    (Some(old), None) => this.push(Difference::Removed(old.clone())),
+                    (None, Some(new)) => this.push(Difference::Added(new.clone())),
+                    (None, None) => (),
+                }
+            })
+        }
+    }
+
+    fn map(&mut self, inner: &Schema, old: &Value, new: &Value) {
+        let (Some(old), Some(new)) = (old.as_map(), new.as_map()) else {
+            return self.leaf(old, new);
+        };
+        for (key, old) in old {
+            self.scope(PathSegment::key(key), |this| {
+                match new.iter().find(|(k, _)| k == key) {
+                    Some((_, new)) => this.node(inner, old, new),
+                    None => this.push(Difference::Removed(old.clone())),
+                }
+            })
+        }
+        for (key, new) in new {
+            if !old.iter().any(|(k, _)| k == key) {
+                self.scope(PathSegment::key(key), |this| {
+                    this.push(Difference::Added(new.clone()))
+                })
+            }
+        }
+    }
+
+    /// A batch or a [`BevyObject`], entries are visited with `f`.
+    fn object(
+        &mut self,
+        fields: &[(String, Schema)],
+        old: &Value,
+        new: &Value,
+        f: fn(&mut Self, &Schema, &Value, &Value),
+    ) {
+        let (Some(old), Some(new)) = (old.as_map(), new.as_map()) else {
+            return self.leaf(old, new);
+        };
+        let schema_of = |key: &str| {
+            fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map_or(&Schema::Any, |(_, schema)| schema)
+        };
+        for (key, old) in old {
+            let Some(name) = key.as_str() else { continue };
+            self.scope(PathSegment::Key(name.to_owned()), |this| {
+                match new.iter().find(|(k, _)| k == key) {
+                    Some((_, new)) => f(this, schema_of(name), old, new),
+                    None => this.push(Difference::Removed(old.clone())),
+                }
+            })
+        }
+        for (key, new) in new {
+            let Some(name) = key.as_str() else { continue };
+            if !old.iter().any(|(k, _)| k == key) {
+                self.scope(PathSegment::Key(name.to_owned()), |this| {
+                    this.push(Difference::Added(new.clone()))
+                })
+            }
+        }
+    }
+}
+
+/// Compact json like formatting of a [`Value`].
+struct Compact<'t>(&'t Value);
+
+impl Display for Compact<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Value::Unit => f.write_str("null"),
+            Value::Bool(v) => write!(f, "{v}"),
+            Value::I64(v) => write!(f, "{v}"),
+            Value::U64(v) => write!(f, "{v}"),
+            Value::F64(v) => write!(f, "{v}"),
+            Value::Char(v) => write!(f, "{:?}", v.to_string()),
+            Value::String(v) => write!(f, "{v:?}"),
+            Value::Bytes(v) => write!(f, "{v:?}"),
+            Value::Seq(items) => {
+                f.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", Compact(item))?;
+                }
+                f.write_char(']')
+            }
+            Value::Map(entries) => {
+                f.write_char('{')?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", Compact(key), Compact(value))?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
@@ -0,0 +1,543 @@
+//! Module for object layouts defined at runtime.
+//!
+//! Components are registered by name in a [`DynamicRegistry`], then object layouts,
+//! a list of component names and child objects, can be defined from data:
+//!
+//! ```
+//! world.register_dynamic_component::<Hp>();
+//! world.register_dynamic_component::<Potion>();
+//! world.register_dynamic_object("Item", DynamicLayout::new(["Potion"]));
+//! world.register_dynamic_object("Unit", DynamicLayout::new(["Hp"]).with_children(["Item"]));
+//! ```
+//!
+//! [`DynamicBevyObject`] saves all registered layouts and can be used in batches
+//! alongside static types:
+//!
+//! ```
+//! type SaveFile = batch!(Character, DynamicBevyObject);
+//! ```
+//!
+//! Which saves a map of layout names to their root objects:
+//!
+//! ```json
+//! {
+//!     "Character": [..],
+//!     "DynamicBevyObject": {
+//!         "Unit": [{ "Hp": 10, "Item": [{ "Potion": "hp" }] }]
+//!     }
+//! }
+//! ```
+//!
+//! # Note
+//!
+//! * An entity matches a layout if it has all of its components,
+//!   layouts with no components never match.
+//! * An entity is saved as the matching layout with the most components,
+//!   or the first by name if tied.
+//! * Entities that are child objects of another dynamic object are not saved as root objects.
+//! * Child objects are found in [`Children`].
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::Entity;
+use bevy::ecs::hierarchy::{ChildOf, Children};
+use bevy::ecs::resource::Resource;
+use bevy::ecs::world::{EntityRef, EntityWorldMut, World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens_core::{DeUtils, ScopeUtils};
+use rustc_hash::FxHashMap;
+use serde::de::{DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{self, PathSegment};
+use crate::{SerializeWorld, ZstInit, filter, transaction};
+
+type InsertFn = Box<dyn FnOnce(&mut EntityWorldMut) + Send>;
+
+/// Type erased functions of a registered component.
+#[derive(Clone, Copy)]
+struct ComponentFns {
+    contains: fn(&EntityRef) -> bool,
+    get: for<'a, 'w> fn(&'a EntityRef<'w>) -> Option<&'a dyn erased_serde::Serialize>,
+    deserialize: for<'de> fn(
+        &mut dyn erased_serde::Deserializer<'de>,
+    ) -> Result<InsertFn, erased_serde::Error>,
+}
+
+impl ComponentFns {
+    fn new<C: Component + Serialize + DeserializeOwned>() -> Self {
+        ComponentFns {
+            contains: |entity| entity.contains::<C>(),
+            get: get_component::<C>,
+            deserialize: deserialize_component::<C>,
+        }
+    }
+}
+
+fn get_component<'a, C: Component + Serialize>(
+    entity: &'a EntityRef<'_>,
+) -> Option<&'a dyn erased_serde::Serialize> {
+    entity
+        .get::<C>()
+        .map(|component| component as &dyn erased_serde::Serialize)
+}
+
+fn deserialize_component<C: Component + DeserializeOwned>(
+    deserializer: &mut dyn erased_serde::Deserializer<'_>,
+) -> Result<InsertFn, erased_serde::Error> {
+    let component = C::deserialize(deserializer)?;
+    Ok(Box::new(move |entity: &mut EntityWorldMut| {
+        entity.insert(component);
+    }))
+}
+
+/// Layout of a dynamic object.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DynamicLayout {
+    /// Names of registered components on the entity.
+    pub components: Vec<String>,
+    /// Names of dynamic objects in [`Children`].
+    #[serde(default)]
+    pub children: Vec<String>,
+}
+
+impl DynamicLayout {
+    /// Create a layout from names of registered components.
+    pub fn new(components: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        DynamicLayout {
+            components: components.into_iter().map(Into::into).collect(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Add names of dynamic objects in [`Children`].
+    pub fn with_children(mut self, children: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.children.extend(children.into_iter().map(Into::into));
+        self
+    }
+}
+
+/// A [`Resource`] that stores registered components and dynamic object layouts.
+#[derive(Resource, Default, Clone)]
+pub struct DynamicRegistry {
+    components: Arc<FxHashMap<String, ComponentFns>>,
+    objects: Arc<BTreeMap<String, DynamicLayout>>,
+}
+
+impl Debug for DynamicRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicRegistry")
+            .field("components", &self.components.keys().collect::<Vec<_>>())
+            .field("objects", &self.objects)
+            .finish()
+    }
+}
+
+impl DynamicRegistry {
+    /// Register a component by [`TypePath::short_type_path`].
+    pub fn register_component<C: Component + Serialize + DeserializeOwned + TypePath>(&mut self) {
+        self.register_component_by_name::<C>(C::short_type_path())
+    }
+
+    /// Register a component by a name specified by the caller.
+    pub fn register_component_by_name<C: Component + Serialize + DeserializeOwned>(
+        &mut self,
+        name: &str,
+    ) {
+        Arc::make_mut(&mut self.components).insert(name.to_owned(), ComponentFns::new::<C>());
+    }
+
+    /// Register or replace a dynamic object layout.
+    pub fn register_object(&mut self, name: &str, layout: DynamicLayout) {
+        Arc::make_mut(&mut self.objects).insert(name.to_owned(), layout);
+    }
+
+    /// Returns the layout of a dynamic object.
+    pub fn object(&self, name: &str) -> Option<&DynamicLayout> {
+        self.objects.get(name)
+    }
+
+    /// Iterate over names and layouts of dynamic objects.
+    pub fn objects(&self) -> impl Iterator<Item = (&str, &DynamicLayout)> {
+        self.objects
+            .iter()
+            .map(|(name, layout)| (name.as_str(), layout))
+    }
+
+    /// Returns true if an entity matches a layout.
+    pub fn matches(&self, layout: &DynamicLayout, entity: &EntityRef) -> bool {
+        !layout.components.is_empty()
+            && layout.components.iter().all(|name| {
+                self.components
+                    .get(name)
+                    .is_some_and(|fns| (fns.contains)(entity))
+            })
+    }
+
+    /// Returns the name of the layout an entity is saved as,
+    /// the matching layout with the most components, or the first by name if tied.
+    pub fn layout_of(&self, entity: &EntityRef) -> Option<&str> {
+        self.objects()
+            .filter(|(_, layout)| self.matches(layout, entity))
+            .min_by_key(|(_, layout)| std::cmp::Reverse(layout.components.len()))
+            .map(|(name, _)| name)
+    }
+
+    /// Returns true if an entity is a child object of another dynamic object.
+    fn is_child_object(&self, world: &World, entity: &EntityRef) -> bool {
+        let Some(name) = self.layout_of(entity) else {
+            return false;
+        };
+        let Some(parent) = entity
+            .get::<ChildOf>()
+            .and_then(|child_of| world.get_entity(child_of.parent()).ok())
+        else {
+            return false;
+        };
+        self.layout_of(&parent)
+            .and_then(|parent| self.object(parent))
+            .is_some_and(|layout| layout.children.iter().any(|child| child == name))
+    }
+
+    /// Returns true if an entity is saved as a root object of a layout.
+    fn is_root(&self, world: &World, entity: &EntityRef) -> bool {
+        self.layout_of(entity).is_some() && !self.is_child_object(world, entity)
+    }
+}
+
+/// All dynamic objects registered in [`DynamicRegistry`], as an item in a batch.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DynamicBevyObject;
+
+impl ZstInit for DynamicBevyObject {
+    fn init() -> Self {
+        DynamicBevyObject
+    }
+}
+
+fn get_registry(world: &World) -> DynamicRegistry {
+    world
+        .get_resource::<DynamicRegistry>()
+        .cloned()
+        .unwrap_or_default()
+}
+
+impl SerializeWorld for DynamicBevyObject {
+    type De = Self;
+
+    fn name() -> &'static str {
+        "DynamicBevyObject"
+    }
+
+    fn serialize<S: Serializer>(world: &mut World, serializer: S) -> Result<S::Ok, S::Error> {
+        let registry = get_registry(world);
+        let mut query = world.query::<EntityRef>();
+        let world: &World = world;
+        let mut roots = vec![Vec::new(); registry.objects.len()];
+        for entity in query.iter(world) {
+            if !filter::is_selected(entity.id()) || !registry.is_root(world, &entity) {
+                continue;
+            }
+            let Some(name) = registry.layout_of(&entity) else {
+                continue;
+            };
+            if let Some(index) = registry.objects.keys().position(|key| key == name) {
+                roots[index].push(entity.id());
+            }
+        }
+        ScopeUtils::serialize_scope(world, || {
+            let mut map = serializer.serialize_map(Some(roots.len()))?;
+            for ((name, layout), entities) in registry.objects().zip(&roots) {
+                map.serialize_entry(
+                    name,
+                    &ObjectsLens {
+                        world,
+                        registry: &registry,
+                        layout,
+                        entities,
+                    },
+                )?;
+            }
+            map.end()
+        })
+    }
+
+    fn despawn(world: &mut World) {
+        let registry = get_registry(world);
+        let mut query = world.query::<EntityRef>();
+        let roots = query
+            .iter(world)
+            .filter(|entity| filter::is_selected(entity.id()) && registry.is_root(world, entity))
+            .map(|entity| entity.id())
+            .collect::<Vec<_>>();
+        for entity in roots {
+            let _ = world.despawn(entity);
+        }
+    }
+}
+
+struct ObjectsLens<'t> {
+    world: &'t World,
+    registry: &'t DynamicRegistry,
+    layout: &'t DynamicLayout,
+    entities: &'t [Entity],
+}
+
+impl Serialize for ObjectsLens<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.entities.len()))?;
+        for entity in self.entities {
+            let Ok(entity) = self.world.get_entity(*entity) else {
+                return Err(serde::ser::Error::custom(format!(
+                    "Entity missing {entity:?}."
+                )));
+            };
+            seq.serialize_element(&ObjectLens {
+                world: self.world,
+                registry: self.registry,
+                layout: self.layout,
+                entity,
+            })?;
+        }
+        seq.end()
+    }
+}
+
+struct ObjectLens<'t> {
+    world: &'t World,
+    registry: &'t DynamicRegistry,
+    layout: &'t DynamicLayout,
+    entity: EntityRef<'t>,
+}
+
+impl Serialize for ObjectLens<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let layout = self.layout;
+        let mut map =
+            serializer.serialize_map(Some(layout.components.len() + layout.children.len()))?;
+        for name in &layout.components {
+            let Some(value) = self
+                .registry
+                .components
+                .get(name)
+                .and_then(|fns| (fns.get)(&self.entity))
+            else {
+                return Err(serde::ser::Error::custom(format!(
+                    "Component {name} missing on {:?}.",
+                    self.entity.id()
+                )));
+            };
+            map.serialize_entry(name, value)?;
+        }
+        for name in &layout.children {
+            let Some(child) = self.registry.object(name) else {
+                return Err(serde::ser::Error::custom(format!(
+                    "Unknown dynamic object {name}."
+                )));
+            };
+            let entities = self
+                .entity
+                .get::<Children>()
+                .into_iter()
+                .flat_map(|children| children.iter().copied())
+                .filter(|entity| {
+                    self.world
+                        .get_entity(*entity)
+                        .is_ok_and(|entity| self.registry.layout_of(&entity) == Some(name.as_str()))
+                })
+                .collect::<Vec<_>>();
+            map.serialize_entry(
+                name,
+                &ObjectsLens {
+                    world: self.world,
+                    registry: self.registry,
+                    layout: child,
+                    entities: &entities,
+                },
+            )?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for DynamicBevyObject {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let registry = DeUtils::with_world_mut::<D, _>(|world| get_registry(world))?;
+        deserializer.deserialize_map(RegistryVisitor(&registry))?;
+        Ok(DynamicBevyObject)
+    }
+}
+
+struct RegistryVisitor<'t>(&'t DynamicRegistry);
+
+impl<'de> Visitor<'de> for RegistryVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a map of dynamic objects")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        while let Some(name) = map.next_key::<String>()? {
+            error::path_scope(
+                || PathSegment::Key(name.clone()),
+                || {
+                    let Some(layout) = self.0.object(&name) else {
+                        return Err(serde::de::Error::custom(format!(
+                            "Unknown dynamic object {name}."
+                        )));
+                    };
+                    map.next_value_seed(ObjectsSeed {
+                        registry: self.0,
+                        layout,
+                        parent: None,
+                    })
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy)]
+struct ObjectsSeed<'t> {
+    registry: &'t DynamicRegistry,
+    layout: &'t DynamicLayout,
+    parent: Option<Entity>,
+}
+
+impl<'de> DeserializeSeed<'de> for ObjectsSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ObjectsSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a sequence of dynamic objects")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut index = 0;
+        while error::path_scope(
+            || PathSegment::Index(index),
+            || seq.next_element_seed(ObjectSeed(self)),
+        )?
+        .is_some()
+        {
+            index += 1;
+        }
+        Ok(())
+    }
+}
+
+struct ObjectSeed<'t>(ObjectsSeed<'t>);
+
+impl<'de> DeserializeSeed<'de> for ObjectSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let parent = self.0.parent;
+        let entity = DeUtils::with_world_mut::<D, _>(|world| {
+            let entity = world.spawn_empty().id();
+            if let Some(parent) = parent {
+                world.entity_mut(parent).add_child(entity);
+            }
+            entity
+        })?;
+        transaction::record_spawn(entity);
+        let result = deserializer.deserialize_map(ObjectVisitor {
+            registry: self.0.registry,
+            layout: self.0.layout,
+            entity,
+        });
+        if result.is_err() {
+            DeUtils::with_world_mut::<D, _>(|world| {
+                let _ = world.despawn(entity);
+            })?;
+        }
+        result
+    }
+}
+
+struct ObjectVisitor<'t> {
+    registry: &'t DynamicRegistry,
+    layout: &'t DynamicLayout,
+    entity: Entity,
+}
+
+impl<'de> Visitor<'de> for ObjectVisitor<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a dynamic object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut found = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            error::path_scope(
+                || PathSegment::Key(key.clone()),
+                || {
+                    if self.layout.components.contains(&key) {
+                        let Some(fns) = self.registry.components.get(&key) else {
+                            return Err(serde::de::Error::custom(format!(
+                                "Unregistered component {key}."
+                            )));
+                        };
+                        let insert = map.next_value_seed(ComponentSeed(fns))?;
+                        DeUtils::with_world_mut_err::<A::Error, _>(|world| {
+                            if let Ok(mut entity) = world.get_entity_mut(self.entity) {
+                                insert(&mut entity);
+                            }
+                        })?;
+                        found.push(key.clone());
+                    } else if self.layout.children.contains(&key) {
+                        let Some(layout) = self.registry.object(&key) else {
+                            return Err(serde::de::Error::custom(format!(
+                                "Unknown dynamic object {key}."
+                            )));
+                        };
+                        map.next_value_seed(ObjectsSeed {
+                            registry: self.registry,
+                            layout,
+                            parent: Some(self.entity),
+                        })?;
+                    } else {
+                        return Err(serde::de::Error::custom(format!("Unknown field {key}.")));
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+        if let Some(missing) = self
+            .layout
+            .components
+            .iter()
+            .find(|name| !found.contains(name))
+        {
+            return Err(serde::de::Error::custom(format!(
+                "Missing component {missing}."
+            )));
+        }
+        Ok(())
+    }
+}
+
+struct ComponentSeed<'t>(&'t ComponentFns);
+
+impl<'de> DeserializeSeed<'de> for ComponentSeed<'_> {
+    type Value = InsertFn;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.0.deserialize)(&mut deserializer).map_err(serde::de::Error::custom)
+    }
+}
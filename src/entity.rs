@@ -0,0 +1,292 @@
+//! Module for serializing [`Entity`] references.
+//!
+//! Entities spawned by `load` are fresh, so an [`Entity`] stored in a component
+//! would point to a stale id after a round trip.
+//!
+//! To fix this, add an [`EntityId`] field to the [`BevyObject`](crate::BevyObject)
+//! that is being referenced, this assigns the object a save local id.
+//! Then serialize references with [`SerializeEntity`]:
+//!
+//! ```
+//! #[derive(Component, Serialize, Deserialize)]
+//! struct Target {
+//!     // Required for remapping forward references.
+//!     #[entities]
+//!     #[serde(with = "SerializeEntity")]
+//!     entity: Entity,
+//! }
+//!
+//! #[derive(BevyObject)]
+//! struct Unit {
+//!     id: EntityId,
+//!     target: Target,
+//! }
+//! ```
+//!
+//! References to objects that are deserialized later in the same `load`
+//! are patched via [`Component::map_entities`] after `load` finishes.
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::{Entity, EntityHashMap};
+use bevy::ecs::world::World;
+use bevy_serde_lens_core::{DeUtils, SerUtils};
+use ref_cast::RefCast;
+use rustc_hash::FxHashMap;
+use scoped_tls_hkt::scoped_thread_local;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+
+use crate::error::{self, PathSegment};
+use crate::schema::{self, Schema};
+use crate::{BindProject, ZstInit, derrorf, impl_with_notation_newtype, serrorf};
+
+scoped_thread_local!(
+    pub(crate) static mut SER_ENTITY_IDS: FxHashMap<Entity, u64>
+);
+
+scoped_thread_local!(
+    pub(crate) static mut DE_ENTITY_MAP: EntityMap
+);
+
+type MapFn = fn(&mut World, Entity, &mut EntityHashMap<Entity>);
+
+/// Per `load` mapping from save local ids to entities.
+#[derive(Default)]
+pub(crate) struct EntityMap {
+    /// Entities that declared an [`EntityId`].
+    entities: FxHashMap<u64, Entity>,
+    /// Empty entities handed out to forward references.
+    placeholders: FxHashMap<u64, Entity>,
+    /// Components that contain placeholders.
+    fixups: Vec<(Entity, MapFn)>,
+    /// Set if a placeholder is handed out.
+    pending: bool,
+}
+
+impl EntityMap {
+    fn get_or_reserve(&mut self, world: &mut World, id: u64) -> Entity {
+        if let Some(entity) = self.entities.get(&id) {
+            return *entity;
+        }
+        self.pending = true;
+        *self
+            .placeholders
+            .entry(id)
+            .or_insert_with(|| world.spawn_empty().id())
+    }
+
+    /// Remap placeholders and despawn them, returns the first undefined id if any.
+    pub(crate) fn apply(self, world: &mut World) -> Result<(), String> {
+        let mut mapper = EntityHashMap::default();
+        let mut missing = None;
+        for (id, placeholder) in &self.placeholders {
+            match self.entities.get(id) {
+                Some(entity) => {
+                    mapper.insert(*placeholder, *entity);
+                }
+                None => missing = Some(*id),
+            }
+        }
+        for (entity, f) in self.fixups {
+            f(world, entity, &mut mapper);
+        }
+        for placeholder in self.placeholders.into_values() {
+            let _ = world.despawn(placeholder);
+        }
+        match missing {
+            Some(id) => Err(format!("Entity {id} is referenced but never defined.")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Point ids and pending remaps of `from` to `to` after a `load_merge` redirect.
+pub(crate) fn redirect(from: Entity, to: Entity) {
+    if !DE_ENTITY_MAP.is_set() {
+        return;
+    }
+    DE_ENTITY_MAP.with(|map| {
+        for entity in map.entities.values_mut() {
+            if *entity == from {
+                *entity = to;
+            }
+        }
+        for (entity, _) in &mut map.fixups {
+            if *entity == from {
+                *entity = to;
+            }
+        }
+    });
+}
+
+fn map_component<T: Component>(
+    world: &mut World,
+    entity: Entity,
+    mapper: &mut EntityHashMap<Entity>,
+) {
+    let Ok(mut entity) = world.get_entity_mut(entity) else {
+        return;
+    };
+    if let Some(mut component) = entity.take::<T>() {
+        T::map_entities(&mut component, mapper);
+        entity.insert(component);
+    }
+}
+
+/// Deserialize a component and insert it into the current entity,
+/// remap the component later if it references entities not yet deserialized.
+///
+/// Errors are located at the component.
+pub(crate) fn insert_component<'de, T: Component, D: Deserializer<'de>>(
+    f: impl FnOnce() -> Result<T, D::Error>,
+) -> Result<(), D::Error> {
+    error::path_scope(PathSegment::component::<T>, || {
+        let (component, pending) = if DE_ENTITY_MAP.is_set() {
+            let prev = DE_ENTITY_MAP.with(|map| std::mem::take(&mut map.pending));
+            let component = f();
+            let pending = DE_ENTITY_MAP.with(|map| std::mem::replace(&mut map.pending, prev));
+            (component?, pending)
+        } else {
+            (f()?, false)
+        };
+        insert::<T, D>(component)?;
+        if pending {
+            let entity = DeUtils::current_entity::<D>()?;
+            DE_ENTITY_MAP.with(|map| map.fixups.push((entity, map_component::<T>)));
+        }
+        Ok(())
+    })
+}
+
+/// Insert a component into the current entity,
+/// journaling the insert and tracking it for `load_merge`.
+pub(crate) fn insert<'de, T: Component, D: Deserializer<'de>>(
+    component: T,
+) -> Result<(), D::Error> {
+    crate::merge::redirect::<T, D>(&component)?;
+    crate::transaction::record_insert::<T, D>()?;
+    DeUtils::insert::<D>(component)?;
+    crate::merge::track(crate::merge::move_component::<T>);
+    Ok(())
+}
+
+fn save_local_id(entity: Entity) -> Option<u64> {
+    if !SER_ENTITY_IDS.is_set() {
+        return None;
+    }
+    Some(SER_ENTITY_IDS.with(|ids| {
+        let len = ids.len() as u64;
+        *ids.entry(entity).or_insert(len)
+    }))
+}
+
+/// Assigns a save local id to the current entity,
+/// so it can be referenced by [`SerializeEntity`].
+///
+/// Not supported in `query` mode.
+pub struct EntityId;
+
+impl Debug for EntityId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EntityId").finish()
+    }
+}
+
+impl ZstInit for EntityId {
+    fn init() -> Self {
+        EntityId
+    }
+}
+
+impl BindProject for EntityId {
+    type To = Self;
+    type Filter = ();
+}
+
+impl Serialize for EntityId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let entity = SerUtils::current_entity::<S>()?;
+        let Some(id) = save_local_id(entity) else {
+            return Err(serrorf!(
+                "cannot serialize `EntityId` outside the `save` context."
+            ));
+        };
+        id.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EntityId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = u64::deserialize(deserializer)?;
+        if let Some(this) = schema::provided(|| Schema::Integer) {
+            return Ok(this);
+        }
+        let entity = DeUtils::current_entity::<D>()?;
+        if !DE_ENTITY_MAP.is_set() {
+            return Err(derrorf!(
+                "cannot deserialize `EntityId` outside the `load` context."
+            ));
+        }
+        DE_ENTITY_MAP.with(|map| {
+            if map.entities.insert(id, entity).is_some() {
+                return Err(derrorf!("Duplicate entity id {id}."));
+            }
+            Ok(EntityId)
+        })
+    }
+}
+
+/// Newtype of [`Entity`] that serializes the save local id of
+/// an entity with an [`EntityId`].
+///
+/// # Errors
+///
+/// * If used outside of `save` or `load`.
+/// * If the referenced entity is never deserialized in the same `load`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, RefCast)]
+#[repr(transparent)]
+pub struct SerializeEntity(pub Entity);
+
+impl Deref for SerializeEntity {
+    type Target = Entity;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for SerializeEntity {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Serialize for SerializeEntity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Some(id) = save_local_id(self.0) else {
+            return Err(serrorf!(
+                "cannot serialize `SerializeEntity` outside the `save` context."
+            ));
+        };
+        id.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SerializeEntity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = u64::deserialize(deserializer)?;
+        if schema::is_tracing() {
+            return Ok(SerializeEntity(Entity::PLACEHOLDER));
+        }
+        if !DE_ENTITY_MAP.is_set() {
+            return Err(derrorf!(
+                "cannot deserialize `SerializeEntity` outside the `load` context."
+            ));
+        }
+        DeUtils::with_world_mut::<D, _>(|world| {
+            SerializeEntity(DE_ENTITY_MAP.with(|map| map.get_or_reserve(world, id)))
+        })
+    }
+}
+
+impl_with_notation_newtype!([] SerializeEntity [] Entity);
@@ -0,0 +1,324 @@
+//! Module for enveloped saves.
+//!
+//! [`Enveloped`] wraps a [`SaveFormat`] and prefixes its output with a header
+//! and a checksum of the payload, optionally compressing the payload.
+//!
+//! ```
+//! world.save_to_path::<SaveData>("saves/1.bsle", Enveloped::new(Json))?;
+//! // The checksum is verified before any entity is spawned.
+//! world.load_enveloped::<SaveData>(&bytes, Json)?;
+//! // The format is read from the header.
+//! world.load_from_path::<SaveData>("saves/1.bsle")?;
+//! ```
+//!
+//! # Layout
+//!
+//! All integers are little endian.
+//!
+//! | field              | size                     |
+//! |--------------------|--------------------------|
+//! | magic `BSLE`       | 4                        |
+//! | envelope version   | 1                        |
+//! | crate version      | 1 + length               |
+//! | format id          | 1 + length               |
+//! | compression        | 1                        |
+//! | decompressed length| 8                        |
+//! | payload length     | 8                        |
+//! | payload `crc32`    | 4                        |
+//! | payload            | payload length           |
+//!
+//! The checksum is computed on the payload as stored, after compression.
+//! Decompression stops at the decompressed length, which cannot exceed
+//! [`MAX_DECOMPRESSED_LENGTH`].
+use std::borrow::Cow;
+
+use bevy::reflect::TypePath;
+
+use crate::error::LoadError;
+use crate::format::{DeserializeFn, FormatError, SaveFormat};
+
+/// Magic bytes at the start of an enveloped save.
+pub const MAGIC: [u8; 4] = *b"BSLE";
+
+/// Version of the envelope layout.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// File extension of enveloped saves, regardless of the wrapped format.
+pub const EXTENSION: &str = "bsle";
+
+/// Maximum length of a decompressed payload, larger saves are rejected before decompressing.
+pub const MAX_DECOMPRESSED_LENGTH: u64 = 1 << 32;
+
+/// Compression of the payload of an enveloped save.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Compression {
+    #[default]
+    None,
+    /// Compress with `deflate` via `flate2`.
+    #[cfg(feature = "deflate")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "deflate")))]
+    Deflate,
+}
+
+impl Compression {
+    fn tag(self) -> u8 {
+        match self {
+            Compression::None => 0,
+            #[cfg(feature = "deflate")]
+            Compression::Deflate => 1,
+        }
+    }
+
+    fn from_tag(tag: u8) -> Result<Self, EnvelopeError> {
+        match tag {
+            0 => Ok(Compression::None),
+            #[cfg(feature = "deflate")]
+            1 => Ok(Compression::Deflate),
+            _ => Err(EnvelopeError::UnsupportedCompression(tag)),
+        }
+    }
+
+    fn compress(self, bytes: Vec<u8>) -> Result<Vec<u8>, EnvelopeError> {
+        match self {
+            Compression::None => Ok(bytes),
+            #[cfg(feature = "deflate")]
+            Compression::Deflate => {
+                use std::io::Write;
+                let mut encoder =
+                    flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&bytes)?;
+                Ok(encoder.finish()?)
+            }
+        }
+    }
+
+    /// Decompress exactly `length` bytes.
+    fn decompress(self, bytes: &[u8], length: u64) -> Result<Cow<'_, [u8]>, EnvelopeError> {
+        let result = match self {
+            Compression::None => Cow::Borrowed(bytes),
+            #[cfg(feature = "deflate")]
+            Compression::Deflate => {
+                use std::io::Read;
+                let mut result = Vec::new();
+                // Read one more byte to detect payloads longer than `length`.
+                flate2::read::DeflateDecoder::new(bytes)
+                    .take(length + 1)
+                    .read_to_end(&mut result)?;
+                Cow::Owned(result)
+            }
+        };
+        if result.len() as u64 != length {
+            return Err(EnvelopeError::DecompressedLengthMismatch(length));
+        }
+        Ok(result)
+    }
+}
+
+/// Error of an enveloped save, returned before the payload is deserialized.
+#[derive(Debug, thiserror::Error)]
+pub enum EnvelopeError {
+    #[error("Not an enveloped save.")]
+    BadMagic,
+    #[error("Unsupported envelope version {0}.")]
+    UnsupportedVersion(u8),
+    #[error("Unsupported compression {0}, is the feature enabled?")]
+    UnsupportedCompression(u8),
+    #[error("Envelope header is truncated.")]
+    Truncated,
+    #[error("Expected format \"{expected}\", found \"{found}\".")]
+    FormatMismatch { expected: String, found: String },
+    #[error("Payload length mismatch, expected {expected} bytes, found {found}.")]
+    LengthMismatch { expected: u64, found: u64 },
+    #[error("Decompressed payload is not {0} bytes long.")]
+    DecompressedLengthMismatch(u64),
+    #[error("Decompressed length {0} exceeds the maximum of {MAX_DECOMPRESSED_LENGTH} bytes.")]
+    TooLarge(u64),
+    #[error("Checksum mismatch, expected {expected:#010x}, found {found:#010x}.")]
+    ChecksumMismatch { expected: u32, found: u32 },
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Load(#[from] LoadError),
+}
+
+/// Header of an enveloped save.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeHeader {
+    /// Version of `bevy_serde_lens` that wrote the save.
+    ///
+    /// This is informational only and not checked when loading,
+    /// use [`Migrations`](crate::migration::Migrations) to version the save data.
+    pub crate_version: String,
+    /// [`SaveFormat::id`] of the payload.
+    pub format: String,
+    pub compression: Compression,
+    /// Length of the payload after decompression.
+    pub decompressed_length: u64,
+    /// Length of the payload as stored.
+    pub length: u64,
+    /// `crc32` of the payload as stored.
+    pub checksum: u32,
+}
+
+fn push_str(bytes: &mut Vec<u8>, s: &str) {
+    let s = &s.as_bytes()[..s.len().min(u8::MAX as usize)];
+    bytes.push(s.len() as u8);
+    bytes.extend_from_slice(s);
+}
+
+/// Wrap a payload in an envelope.
+pub fn seal(
+    payload: Vec<u8>,
+    format: &str,
+    compression: Compression,
+) -> Result<Vec<u8>, EnvelopeError> {
+    let decompressed_length = payload.len() as u64;
+    let payload = compression.compress(payload)?;
+    let mut bytes = Vec::with_capacity(payload.len() + 72);
+    bytes.extend_from_slice(&MAGIC);
+    bytes.push(ENVELOPE_VERSION);
+    push_str(&mut bytes, env!("CARGO_PKG_VERSION"));
+    push_str(&mut bytes, format);
+    bytes.push(compression.tag());
+    bytes.extend_from_slice(&decompressed_length.to_le_bytes());
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+    Ok(bytes)
+}
+
+struct Cursor<'t>(&'t [u8]);
+
+impl<'t> Cursor<'t> {
+    fn take(&mut self, len: usize) -> Result<&'t [u8], EnvelopeError> {
+        if self.0.len() < len {
+            return Err(EnvelopeError::Truncated);
+        }
+        let (result, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(result)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], EnvelopeError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn take_str(&mut self) -> Result<String, EnvelopeError> {
+        let [len] = self.take_array()?;
+        Ok(String::from_utf8_lossy(self.take(len as usize)?).into_owned())
+    }
+}
+
+/// Read the header of an enveloped save without verifying the payload.
+pub fn read_header(bytes: &[u8]) -> Result<(EnvelopeHeader, &[u8]), EnvelopeError> {
+    let mut cursor = Cursor(bytes);
+    if cursor.take(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+        return Err(EnvelopeError::BadMagic);
+    }
+    let [version] = cursor.take_array()?;
+    if version != ENVELOPE_VERSION {
+        return Err(EnvelopeError::UnsupportedVersion(version));
+    }
+    let crate_version = cursor.take_str()?;
+    let format = cursor.take_str()?;
+    let [compression] = cursor.take_array()?;
+    let compression = Compression::from_tag(compression)?;
+    let decompressed_length = u64::from_le_bytes(cursor.take_array()?);
+    let length = u64::from_le_bytes(cursor.take_array()?);
+    let checksum = u32::from_le_bytes(cursor.take_array()?);
+    let header = EnvelopeHeader {
+        crate_version,
+        format,
+        compression,
+        decompressed_length,
+        length,
+        checksum,
+    };
+    Ok((header, cursor.0))
+}
+
+/// Verify an enveloped save and return its decompressed payload.
+///
+/// If `format` is `Some`, the format id in the header must match.
+pub fn open<'t>(
+    bytes: &'t [u8],
+    format: Option<&str>,
+) -> Result<(EnvelopeHeader, Cow<'t, [u8]>), EnvelopeError> {
+    let (header, payload) = read_header(bytes)?;
+    if let Some(format) = format {
+        if header.format != format {
+            return Err(EnvelopeError::FormatMismatch {
+                expected: format.to_owned(),
+                found: header.format,
+            });
+        }
+    }
+    if header.length != payload.len() as u64 {
+        return Err(EnvelopeError::LengthMismatch {
+            expected: header.length,
+            found: payload.len() as u64,
+        });
+    }
+    let checksum = crc32fast::hash(payload);
+    if header.checksum != checksum {
+        return Err(EnvelopeError::ChecksumMismatch {
+            expected: header.checksum,
+            found: checksum,
+        });
+    }
+    if header.decompressed_length > MAX_DECOMPRESSED_LENGTH {
+        return Err(EnvelopeError::TooLarge(header.decompressed_length));
+    }
+    let payload = header
+        .compression
+        .decompress(payload, header.decompressed_length)?;
+    Ok((header, payload))
+}
+
+/// A [`SaveFormat`] that wraps the output of another [`SaveFormat`] in an envelope.
+///
+/// Errors of the envelope are returned as boxed [`EnvelopeError`]s,
+/// use `load_enveloped` to obtain them directly.
+#[derive(Debug, Clone, Copy, Default, TypePath)]
+pub struct Enveloped<F> {
+    pub format: F,
+    pub compression: Compression,
+}
+
+impl<F: SaveFormat> Enveloped<F> {
+    /// Wrap a [`SaveFormat`] without compression.
+    pub fn new(format: F) -> Self {
+        Enveloped {
+            format,
+            compression: Compression::None,
+        }
+    }
+
+    /// Wrap a [`SaveFormat`] with compression.
+    pub fn compressed(format: F, compression: Compression) -> Self {
+        Enveloped {
+            format,
+            compression,
+        }
+    }
+}
+
+impl<F: SaveFormat> SaveFormat for Enveloped<F> {
+    fn extensions(&self) -> &'static [&'static str] {
+        &[EXTENSION]
+    }
+
+    fn id(&self) -> &'static str {
+        self.format.id()
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        let payload = self.format.serialize(value)?;
+        Ok(seal(payload, self.format.id(), self.compression)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let (_, payload) = open(bytes, Some(self.format.id()))?;
+        self.format.deserialize(&payload, f)
+    }
+}
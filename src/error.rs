@@ -0,0 +1,178 @@
+//! Module for locating deserialization errors.
+//!
+//! During `load`, batch keys, root indices, children and components are tracked
+//! as a path, errors returned by `load` are prefixed with the path where they occurred:
+//!
+//! ```text
+//! Unit[3][1].Hp: invalid type: string "full", expected u32
+//! ```
+//!
+//! [`WorldExtension::try_load`] returns a [`LoadError`] with the path as structured data,
+//! [`WorldExtension::load_lenient`] returns a [`LoadError`] for each skipped object.
+use std::fmt::{Display, Write};
+
+use scoped_tls_hkt::scoped_thread_local;
+use serde::Serialize;
+
+use crate::value::{Value, to_value};
+
+#[allow(unused)]
+use crate::WorldExtension;
+
+scoped_thread_local!(
+    pub(crate) static mut ERROR_PATH: PathStack
+);
+
+/// A segment in the path of a [`LoadError`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A name in a batch or a key in a `ChildMap`.
+    Key(String),
+    /// An index in a `Root` or a `ChildVec`.
+    Index(usize),
+    /// Short type name of a component.
+    Component(&'static str),
+}
+
+impl PathSegment {
+    /// Create a [`PathSegment::Component`] from a type.
+    pub fn component<T>() -> Self {
+        let name = std::any::type_name::<T>();
+        let name = name.split('<').next().unwrap_or(name);
+        PathSegment::Component(name.rsplit("::").next().unwrap_or(name))
+    }
+
+    /// Create a [`PathSegment::Key`] from a serializable key.
+    pub fn key<T: Serialize + ?Sized>(key: &T) -> Self {
+        PathSegment::Key(match to_value(key) {
+            Ok(Value::String(s)) => s,
+            Ok(Value::Bool(v)) => v.to_string(),
+            Ok(Value::I64(v)) => v.to_string(),
+            Ok(Value::U64(v)) => v.to_string(),
+            Ok(Value::F64(v)) => v.to_string(),
+            Ok(Value::Char(v)) => v.to_string(),
+            _ => "?".to_owned(),
+        })
+    }
+}
+
+/// Path to an error in a save file, like `Unit[3][1].Hp`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ErrorPath(pub Vec<PathSegment>);
+
+impl ErrorPath {
+    /// Segments of the path from the root of the save.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    /// Returns true if the error occurred at the root of the save.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Path of the current `load`.
+#[derive(Default)]
+pub(crate) struct PathStack {
+    segments: Vec<PathSegment>,
+    /// Path of the first error.
+    failed: Option<ErrorPath>,
+}
+
+impl PathStack {
+    /// Take the path of the first error.
+    pub(crate) fn take_failed(&mut self) -> ErrorPath {
+        self.failed.take().unwrap_or_default()
+    }
+}
+
+impl Display for ErrorPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            let name = match segment {
+                PathSegment::Index(index) => {
+                    write!(f, "[{index}]")?;
+                    continue;
+                }
+                PathSegment::Key(key) => key.as_str(),
+                PathSegment::Component(name) => *name,
+            };
+            if i > 0 {
+                f.write_char('.')?;
+            }
+            f.write_str(name)?;
+        }
+        Ok(())
+    }
+}
+
+/// A deserialization error with the path where it occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadError {
+    /// Path to the error, empty if unknown.
+    pub path: ErrorPath,
+    /// Message of the underlying error.
+    pub message: String,
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl serde::de::Error for LoadError {
+    fn custom<T: Display>(msg: T) -> Self {
+        LoadError {
+            path: ErrorPath::default(),
+            message: msg.to_string(),
+        }
+    }
+}
+
+impl LoadError {
+    /// Create a [`LoadError`] from the path of the current `load` and an error.
+    pub(crate) fn new(path: ErrorPath, error: impl Display) -> Self {
+        LoadError {
+            path,
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Take the path of the first error since the last call, or the current path if not found.
+pub(crate) fn take_error_path() -> ErrorPath {
+    if !ERROR_PATH.is_set() {
+        return ErrorPath::default();
+    }
+    ERROR_PATH.with(|path| {
+        path.failed
+            .take()
+            .unwrap_or_else(|| ErrorPath(path.segments.clone()))
+    })
+}
+
+/// Push a segment to the path while running `f`, the path is saved if `f` is the first to fail.
+pub(crate) fn path_scope<T, E>(
+    segment: impl FnOnce() -> PathSegment,
+    f: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    if !ERROR_PATH.is_set() {
+        return f();
+    }
+    ERROR_PATH.with(|path| path.segments.push(segment()));
+    let result = f();
+    ERROR_PATH.with(|path| {
+        if result.is_err() && path.failed.is_none() {
+            path.failed = Some(ErrorPath(path.segments.clone()));
+        }
+        path.segments.pop();
+    });
+    result
+}
@@ -1,15 +1,36 @@
-use crate::BatchSerialization;
 use crate::asset::{DE_REUSABLE_HANDLES, SER_REUSABLE_HANDLES};
+use crate::delta::{self, Delta, DeltaBaseline, DeltaLens};
+use crate::diff::{self, WorldDiff};
+use crate::dynamic::{DynamicLayout, DynamicRegistry};
+use crate::entity::{DE_ENTITY_MAP, EntityMap, SER_ENTITY_IDS};
+use crate::envelope::{self, EnvelopeError, Enveloped};
+use crate::error::{ERROR_PATH, ErrorPath, LoadError, PathStack};
+use crate::filter;
+use crate::format::{ParallelFormat, SaveFormat, enabled_formats, format_from_path, write_atomic};
+use crate::lenient::DIAGNOSTICS;
+use crate::merge::{MERGE_SCOPE, MergeScope, Unmatched};
+use crate::migration::{Migrations, VersionedSave, split_header};
+use crate::parallel;
+use crate::save_file::SaveFileError;
+use crate::schema::{self, RootSchema};
+use crate::transaction::{self, RollbackRegistry};
 use crate::typetagged::TYPETAG_SERVER;
 use crate::typetagged::{ErasedObject, TypeTagServer};
+use crate::value::{self, Value, ValueSerializer};
+use crate::{BatchSerialization, BevyObject, EntitySubset, OwnedObject};
 use bevy::app::App;
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::Entity;
 use bevy::ecs::resource::Resource;
 use bevy::ecs::world::World;
 use bevy::reflect::TypePath;
 use bevy_serde_lens_core::ScopeUtils;
+use rustc_hash::FxHashMap;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::hash::Hash;
 use std::marker::PhantomData;
+use std::path::Path;
 use std::sync::Mutex;
 
 #[allow(unused)]
@@ -26,8 +47,31 @@ pub trait WorldExtension {
         &mut self,
         serializer: S,
     ) -> Result<S::Ok, S::Error>;
+    /// Save a [`BatchSerialization`] type, only root objects whose entities
+    /// are in an [`EntitySubset`] are saved.
+    ///
+    /// Children of saved root objects are saved regardless of the subset.
+    ///
+    /// ```
+    /// // Save units in a region.
+    /// world.save_filtered::<SaveFile, _>(serializer, |entity: &EntityRef| {
+    ///     entity.get::<Position>().is_some_and(|p| region.contains(p))
+    /// })?;
+    /// // Save selected prefabs.
+    /// world.save_filtered::<Prefab, _>(serializer, selected.as_slice())?;
+    /// ```
+    fn save_filtered<T: BatchSerialization, S: Serializer>(
+        &mut self,
+        serializer: S,
+        subset: impl EntitySubset,
+    ) -> Result<S::Ok, S::Error>;
     /// Load a [`BatchSerialization`] type.
     ///
+    /// If an error occurs, changes made by this `load` are rolled back:
+    /// spawned entities are despawned and replaced components, resources and states are restored.
+    /// Resources changed in place, like an [`Interner`](crate::interning::Interner),
+    /// are restored if registered with `register_rollback`.
+    ///
     /// # What's a [`Deserializer`]?
     ///
     /// Most `serde` frontends provide a serializer, like `serde_json::Deserializer`.
@@ -35,14 +79,178 @@ pub trait WorldExtension {
         &mut self,
         deserializer: D,
     ) -> Result<(), D::Error>;
+    /// Load a [`BatchSerialization`] type, returns a [`LoadError`] containing
+    /// the path to the error in the save file if failed.
+    ///
+    /// Errors returned by `load` contain the same path in their messages.
+    fn try_load<'de, T: BatchSerialization, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<(), LoadError>;
+    /// Load a [`BatchSerialization`] type, objects in `Root`, [`ChildVec`](crate::ChildVec)
+    /// or [`ChildMap`](crate::ChildMap) that fail to deserialize are skipped
+    /// and returned as diagnostics.
+    ///
+    /// Requires a self describing format, since each object is buffered before deserialization.
+    ///
+    /// # Note
+    ///
+    /// Errors outside of these objects, like syntax errors, still fail the `load`.
+    fn load_lenient<'de, T: BatchSerialization, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<Vec<LoadError>, D::Error>;
+    /// Save a [`BatchSerialization`] type as bytes in a [`SaveFormat`].
+    fn save_to_bytes<T: BatchSerialization>(
+        &mut self,
+        format: impl SaveFormat,
+    ) -> Result<Vec<u8>, SaveFileError>;
+    /// Save a [`BevyObject`] as bytes in a [`ParallelFormat`], if `IS_QUERY`,
+    /// objects are serialized in chunks on the
+    /// [`ComputeTaskPool`](bevy::tasks::ComputeTaskPool) and joined into a single sequence.
+    ///
+    /// The result can be loaded with `load_from_bytes`.
+    ///
+    /// # Note
+    ///
+    /// Contexts of `save` are not available while serializing in parallel,
+    /// including [`EntityId`](crate::entity::EntityId) references,
+    /// reused handles and registered extractions.
+    fn save_parallel<T: BevyObject>(
+        &mut self,
+        format: impl ParallelFormat,
+    ) -> Result<Vec<u8>, SaveFileError>;
+    /// Load a [`OwnedObject`] from bytes in a [`ParallelFormat`], objects are deserialized
+    /// in chunks on the [`ComputeTaskPool`](bevy::tasks::ComputeTaskPool) without world access,
+    /// then spawned in a batch.
+    ///
+    /// Nothing is spawned if any object fails to deserialize.
+    ///
+    /// # Note
+    ///
+    /// Contexts of `load` are not available, including
+    /// [`EntityId`](crate::entity::EntityId) references and handles.
+    fn load_parallel<T: OwnedObject>(
+        &mut self,
+        bytes: &[u8],
+        format: impl ParallelFormat,
+    ) -> Result<(), LoadError>;
+    /// Load a [`BatchSerialization`] type from bytes in a [`SaveFormat`].
+    fn load_from_bytes<T: BatchSerialization>(
+        &mut self,
+        bytes: &[u8],
+        format: impl SaveFormat,
+    ) -> Result<(), LoadError>;
+    /// Save a [`BatchSerialization`] type to a file in a [`SaveFormat`].
+    ///
+    /// The save is written to a temporary file first, then renamed to `path`,
+    /// so an existing save is never left partially written.
+    fn save_to_path<T: BatchSerialization>(
+        &mut self,
+        path: impl AsRef<Path>,
+        format: impl SaveFormat,
+    ) -> Result<(), SaveFileError>;
+    /// Load a [`BatchSerialization`] type from a file,
+    /// the format is detected from the file extension among enabled formats.
+    ///
+    /// Files with the [`EXTENSION`](crate::envelope::EXTENSION) of enveloped saves
+    /// are loaded with the format in their header.
+    ///
+    /// Use `load_from_bytes` for custom formats.
+    fn load_from_path<T: BatchSerialization>(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<(), LoadError>;
+    /// Load a [`BatchSerialization`] type from bytes wrapped in an
+    /// [`Enveloped`](crate::envelope::Enveloped) [`SaveFormat`].
+    ///
+    /// The header and checksum are verified before any entity is spawned.
+    fn load_enveloped<T: BatchSerialization>(
+        &mut self,
+        bytes: &[u8],
+        format: impl SaveFormat,
+    ) -> Result<(), EnvelopeError>;
+    /// Load a [`BevyObject`], root objects whose key component `K` matches
+    /// an existing entity of `T` overwrite that entity instead of spawning a new one.
+    ///
+    /// Unmatched root objects are spawned and existing entities not in the save
+    /// are kept or despawned depending on `unmatched`.
+    ///
+    /// # Note
+    ///
+    /// * `K` must be a plain component, fields before `K` are moved to the matched entity.
+    /// * [`Children`](bevy::ecs::hierarchy::Children) of matched entities
+    ///   are despawned and deserialized again.
+    fn load_merge<'de, T: BevyObject, K: Component + Eq + Hash + Clone, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+        unmatched: Unmatched,
+    ) -> Result<(), D::Error>;
+    /// Record keys and the change tick of a [`BevyObject`] for `save_delta`.
+    fn delta_baseline<T: BevyObject, K: Component + Eq + Hash + Clone>(
+        &mut self,
+    ) -> DeltaBaseline<K>;
+    /// Save objects of a [`BevyObject`] spawned, changed or despawned since a [`DeltaBaseline`],
+    /// identified by the key component `K`.
+    ///
+    /// See the [`delta`](crate::delta) module for details.
+    fn save_delta<T: BevyObject, K: Component + Eq + Hash + Clone + Serialize, S: Serializer>(
+        &mut self,
+        baseline: &DeltaBaseline<K>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+    /// Apply a delta created by `save_delta` on top of a previously loaded save.
+    ///
+    /// Changed objects overwrite existing entities with the same key `K`
+    /// like in `load_merge`, then despawned objects are despawned.
+    fn load_delta<
+        'de,
+        T: BevyObject,
+        K: Component + Eq + Hash + Clone + DeserializeOwned,
+        D: Deserializer<'de>,
+    >(
+        &mut self,
+        deserializer: D,
+    ) -> Result<(), D::Error>;
+    /// Save a [`BatchSerialization`] type with a header containing versions
+    /// of registered [`Migrations`].
+    fn save_versioned<T: BatchSerialization, S: Serializer>(
+        &mut self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+    /// Load a [`BatchSerialization`] type saved by `save_versioned`,
+    /// upgrading outdated values with registered [`Migrations`].
+    ///
+    /// Requires a self describing format.
+    fn load_versioned<'de, T: BatchSerialization, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<(), D::Error>;
+    /// Compare this world with another world under a [`BatchSerialization`] type.
+    ///
+    /// This world is the old side of the report. See the [`diff`](crate::diff) module for details.
+    fn diff<T: BatchSerialization>(&mut self, other: &mut World)
+    -> Result<WorldDiff, value::Error>;
+    /// Compare a save of a [`BatchSerialization`] type with this world.
+    ///
+    /// The save is the old side of the report.
+    fn diff_save<T: BatchSerialization>(&mut self, save: &Value)
+    -> Result<WorldDiff, value::Error>;
     /// Create a [`Serialize`] type from a [`World`] and a [`BatchSerialization`] type.
     fn serialize_lens<S: BatchSerialization>(&mut self) -> SerializeLens<'_, S>;
     /// Create a [`Deserialize`] scope from a [`World`].
     ///
     /// [`InWorld`] can be used inside the scope.
+    ///
+    /// Unlike `load`, references to undefined [`EntityId`](crate::entity::EntityId)s
+    /// are not reported.
     fn deserialize_scope<T>(&mut self, f: impl FnOnce() -> T) -> T;
     /// Despawn all entities in a [`BatchSerialization`] type recursively.
     fn despawn_bound_objects<T: BatchSerialization>(&mut self);
+    /// Export the layout of a [`BatchSerialization`] type as JSON Schema.
+    ///
+    /// Registered type tags are included in the schema.
+    fn schema<T: BatchSerialization>(&self) -> RootSchema;
     /// Register a type that can be deserialized via a type tag.
     ///
     /// The name of the type is [`TypePath::short_type_path`] and must be unique.
@@ -55,6 +263,33 @@ pub trait WorldExtension {
         name: &str,
     );
 
+    /// Register a component by [`TypePath::short_type_path`],
+    /// so it can be used in [`DynamicLayout`]s.
+    fn register_dynamic_component<C: Component + Serialize + DeserializeOwned + TypePath>(
+        &mut self,
+    );
+    /// Register or replace a dynamic object layout,
+    /// saved and loaded by [`DynamicBevyObject`](crate::dynamic::DynamicBevyObject).
+    fn register_dynamic_object(&mut self, name: &str, layout: DynamicLayout);
+
+    /// Register a migration of a [`BevyObject`] or [`SerializeWorld`](crate::SerializeWorld)
+    /// by name from version `from` to `from + 1`.
+    fn register_migration(
+        &mut self,
+        name: &str,
+        from: u32,
+        migration: impl Fn(&mut Value) -> Result<(), String> + Send + Sync + 'static,
+    );
+    /// Register a renamed object, values saved under `old` are loaded as `new`
+    /// by `load_versioned`.
+    fn register_migration_alias(&mut self, old: &str, new: &str);
+
+    /// Restore a resource changed in place by a failed `load`,
+    /// like an [`Interner`](crate::interning::Interner) that values are added to.
+    ///
+    /// `R` is cloned before its first change in each `load`.
+    fn register_rollback<R: Resource + Clone>(&mut self);
+
     /// When serializing, extract a resource into a thread local scope.
     ///
     /// To implement this, push `R` into a scope then call the `FnMut`,
@@ -79,41 +314,249 @@ impl WorldExtension for World {
         &mut self,
         serializer: S,
     ) -> Result<S::Ok, S::Error> {
-        self.init_resource::<RegisteredExtractions>();
-        let mut serializer = Some(serializer);
-        let mut result = None;
+        save_scope(self, |world| T::serialize(world, serializer))
+    }
 
-        let mut handles = Default::default();
-        SER_REUSABLE_HANDLES.set(&mut handles, || {
-            self.resource_scope::<RegisteredExtractions, _>(|world, extractions| {
-                (extractions.ser)(world, &mut |world| {
-                    result = Some(T::serialize(world, serializer.take().unwrap()))
-                })
-            });
-        });
-        result.unwrap()
+    fn save_filtered<T: BatchSerialization, S: Serializer>(
+        &mut self,
+        serializer: S,
+        subset: impl EntitySubset,
+    ) -> Result<S::Ok, S::Error> {
+        let entities = subset.collect(self);
+        filter::filter_roots(&entities, || self.save::<T, S>(serializer))
     }
 
     fn load<'de, T: BatchSerialization, D: Deserializer<'de>>(
         &mut self,
         deserializer: D,
     ) -> Result<(), D::Error> {
-        self.init_resource::<RegisteredExtractions>();
-        let mut deserializer = Some(deserializer);
-        let mut result = None;
+        load_located::<T, D>(self, deserializer).map_err(located_error)
+    }
 
-        let mut handles = Default::default();
-        DE_REUSABLE_HANDLES.set(&mut handles, || {
-            self.resource_scope::<RegisteredExtractions, _>(|world, extractions| {
-                (extractions.de)(world, &mut |world| {
-                    result = Some(ScopeUtils::deserialize_scope(world, || {
-                        T::De::deserialize(deserializer.take().unwrap())
-                    }))
-                })
+    fn try_load<'de, T: BatchSerialization, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<(), LoadError> {
+        load_located::<T, D>(self, deserializer).map_err(|(path, e)| LoadError::new(path, e))
+    }
+
+    fn load_lenient<'de, T: BatchSerialization, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<Vec<LoadError>, D::Error> {
+        let mut diagnostics = Vec::new();
+        DIAGNOSTICS.set(&mut diagnostics, || self.load::<T, D>(deserializer))?;
+        Ok(diagnostics)
+    }
+
+    fn save_to_bytes<T: BatchSerialization>(
+        &mut self,
+        format: impl SaveFormat,
+    ) -> Result<Vec<u8>, SaveFileError> {
+        format
+            .serialize(&self.serialize_lens::<T>())
+            .map_err(SaveFileError::Format)
+    }
+
+    fn save_parallel<T: BevyObject>(
+        &mut self,
+        format: impl ParallelFormat,
+    ) -> Result<Vec<u8>, SaveFileError> {
+        parallel::save_parallel::<T>(self, &format)
+    }
+
+    fn load_parallel<T: OwnedObject>(
+        &mut self,
+        bytes: &[u8],
+        format: impl ParallelFormat,
+    ) -> Result<(), LoadError> {
+        parallel::load_parallel::<T>(self, bytes, &format)
+    }
+
+    fn load_from_bytes<T: BatchSerialization>(
+        &mut self,
+        bytes: &[u8],
+        format: impl SaveFormat,
+    ) -> Result<(), LoadError> {
+        // Trailing bytes are checked before changes are committed.
+        transaction::scope(self, |world| {
+            let mut result = Ok(());
+            let parsed = format.deserialize(bytes, &mut |deserializer| {
+                result = world.try_load::<T, _>(deserializer);
+                Ok(())
             });
-        });
-        // Discard the zst.
-        result.unwrap().map(|_| ())
+            // Report errors in the save before trailing bytes.
+            result?;
+            parsed.map_err(serde::de::Error::custom)
+        })
+    }
+
+    fn save_to_path<T: BatchSerialization>(
+        &mut self,
+        path: impl AsRef<Path>,
+        format: impl SaveFormat,
+    ) -> Result<(), SaveFileError> {
+        let bytes = self.save_to_bytes::<T>(format)?;
+        write_atomic(path.as_ref(), &bytes)?;
+        Ok(())
+    }
+
+    fn load_from_path<T: BatchSerialization>(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<(), LoadError> {
+        let path = path.as_ref();
+        let enveloped = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(envelope::EXTENSION));
+        if enveloped {
+            let bytes = std::fs::read(path).map_err(serde::de::Error::custom)?;
+            let (header, _) = envelope::read_header(&bytes).map_err(serde::de::Error::custom)?;
+            let Some(format) = enabled_formats()
+                .iter()
+                .find(|format| format.id() == header.format)
+            else {
+                return Err(serde::de::Error::custom(format!(
+                    "No enabled format \"{}\" for {}.",
+                    header.format,
+                    path.display()
+                )));
+            };
+            return self.load_from_bytes::<T>(&bytes, Enveloped::new(*format));
+        }
+        let Some(format) = format_from_path(path) else {
+            return Err(serde::de::Error::custom(format!(
+                "No enabled format for {}.",
+                path.display()
+            )));
+        };
+        let bytes = std::fs::read(path).map_err(serde::de::Error::custom)?;
+        self.load_from_bytes::<T>(&bytes, format)
+    }
+
+    fn load_enveloped<T: BatchSerialization>(
+        &mut self,
+        bytes: &[u8],
+        format: impl SaveFormat,
+    ) -> Result<(), EnvelopeError> {
+        let (_, payload) = envelope::open(bytes, Some(format.id()))?;
+        self.load_from_bytes::<T>(&payload, format)?;
+        Ok(())
+    }
+
+    fn load_merge<'de, T: BevyObject, K: Component + Eq + Hash + Clone, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+        unmatched: Unmatched,
+    ) -> Result<(), D::Error> {
+        let mut query = self.query_filtered::<(Entity, &K), T::Filter>();
+        let candidates: FxHashMap<K, Entity> = query
+            .iter(self)
+            .map(|(entity, key)| (key.clone(), entity))
+            .collect();
+        let mut scope = MergeScope::new(candidates);
+        MERGE_SCOPE.set(&mut scope, || self.load::<T, D>(deserializer))?;
+        if unmatched == Unmatched::Despawn {
+            for entity in scope.unmatched::<K>() {
+                let _ = self.despawn(entity);
+            }
+            self.flush();
+        }
+        Ok(())
+    }
+
+    fn delta_baseline<T: BevyObject, K: Component + Eq + Hash + Clone>(
+        &mut self,
+    ) -> DeltaBaseline<K> {
+        delta::baseline::<T, K>(self)
+    }
+
+    fn save_delta<T: BevyObject, K: Component + Eq + Hash + Clone + Serialize, S: Serializer>(
+        &mut self,
+        baseline: &DeltaBaseline<K>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        save_scope(self, |world| {
+            DeltaLens::<T, K>::new(world, baseline).serialize(serializer)
+        })
+    }
+
+    fn load_delta<
+        'de,
+        T: BevyObject,
+        K: Component + Eq + Hash + Clone + DeserializeOwned,
+        D: Deserializer<'de>,
+    >(
+        &mut self,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        let mut query = self.query_filtered::<(Entity, &K), T::Filter>();
+        let candidates: FxHashMap<K, Entity> = query
+            .iter(self)
+            .map(|(entity, key)| (key.clone(), entity))
+            .collect();
+        let mut scope = MergeScope::new(candidates.clone());
+        let despawned = MERGE_SCOPE
+            .set(&mut scope, || {
+                load_located_with(self, deserializer, |de| {
+                    Delta::<T, K>::deserialize(de).map(|delta| delta.despawned)
+                })
+            })
+            .map_err(located_error)?;
+        for key in despawned {
+            if let Some(entity) = candidates.get(&key) {
+                let _ = self.despawn(*entity);
+            }
+        }
+        self.flush();
+        Ok(())
+    }
+
+    fn save_versioned<T: BatchSerialization, S: Serializer>(
+        &mut self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let versions = self
+            .get_resource::<Migrations>()
+            .map(Migrations::versions)
+            .unwrap_or_default();
+        VersionedSave {
+            versions,
+            data: self.serialize_lens::<T>(),
+        }
+        .serialize(serializer)
+    }
+
+    fn load_versioned<'de, T: BatchSerialization, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let (versions, mut data) = split_header(value).map_err(serde::de::Error::custom)?;
+        if let Some(migrations) = self.get_resource::<Migrations>() {
+            T::migrate(&mut data, &mut |name, value| {
+                migrations.migrate_saved(name, &versions, value)
+            })
+            .map_err(serde::de::Error::custom)?;
+        }
+        self.load::<T, _>(data).map_err(serde::de::Error::custom)
+    }
+
+    fn diff<T: BatchSerialization>(
+        &mut self,
+        other: &mut World,
+    ) -> Result<WorldDiff, value::Error> {
+        let old = self.save::<T, _>(ValueSerializer)?;
+        let new = other.save::<T, _>(ValueSerializer)?;
+        Ok(diff::diff::<T>(&old, &new))
+    }
+
+    fn diff_save<T: BatchSerialization>(
+        &mut self,
+        save: &Value,
+    ) -> Result<WorldDiff, value::Error> {
+        let new = self.save::<T, _>(ValueSerializer)?;
+        Ok(diff::diff::<T>(save, &new))
     }
 
     fn serialize_lens<S: BatchSerialization>(&mut self) -> SerializeLens<'_, S> {
@@ -125,13 +568,18 @@ impl WorldExtension for World {
         let mut f = Some(f);
         let mut result = None;
         let mut handles = Default::default();
+        let mut entities = EntityMap::default();
         DE_REUSABLE_HANDLES.set(&mut handles, || {
-            self.resource_scope::<RegisteredExtractions, _>(|world, extractions| {
-                (extractions.de)(world, &mut |world| {
-                    result = Some(ScopeUtils::deserialize_scope(world, f.take().unwrap()))
-                })
-            });
+            DE_ENTITY_MAP.set(&mut entities, || {
+                self.resource_scope::<RegisteredExtractions, _>(|world, extractions| {
+                    (extractions.de)(world, &mut |world| {
+                        result = Some(ScopeUtils::deserialize_scope(world, f.take().unwrap()))
+                    })
+                });
+            })
         });
+        // Errors cannot be reported here, undefined references are left as is.
+        let _ = entities.apply(self);
         result.unwrap()
     }
 
@@ -141,6 +589,13 @@ impl WorldExtension for World {
         self.flush();
     }
 
+    fn schema<T: BatchSerialization>(&self) -> RootSchema {
+        match self.get_resource::<TypeTagServer>() {
+            Some(server) => TYPETAG_SERVER.set(server, schema::root_schema::<T>),
+            None => schema::root_schema::<T>(),
+        }
+    }
+
     fn register_typetag<A: ErasedObject, B: Into<A> + TypePath + DeserializeOwned>(&mut self) {
         let mut server = self.get_resource_or_insert_with(TypeTagServer::default);
         server.register::<A, B>()
@@ -154,6 +609,38 @@ impl WorldExtension for World {
         server.register_by_name::<A, B>(name)
     }
 
+    fn register_dynamic_component<C: Component + Serialize + DeserializeOwned + TypePath>(
+        &mut self,
+    ) {
+        let mut registry = self.get_resource_or_insert_with(DynamicRegistry::default);
+        registry.register_component::<C>()
+    }
+
+    fn register_dynamic_object(&mut self, name: &str, layout: DynamicLayout) {
+        let mut registry = self.get_resource_or_insert_with(DynamicRegistry::default);
+        registry.register_object(name, layout)
+    }
+
+    fn register_migration(
+        &mut self,
+        name: &str,
+        from: u32,
+        migration: impl Fn(&mut Value) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        let mut migrations = self.get_resource_or_insert_with(Migrations::default);
+        migrations.register(name, from, migration)
+    }
+
+    fn register_migration_alias(&mut self, old: &str, new: &str) {
+        let mut migrations = self.get_resource_or_insert_with(Migrations::default);
+        migrations.register_alias(old, new)
+    }
+
+    fn register_rollback<R: Resource + Clone>(&mut self) {
+        let mut registry = self.get_resource_or_insert_with(RollbackRegistry::default);
+        registry.register::<R>()
+    }
+
     fn register_serialize_resource_cx<R: Resource>(
         &mut self,
         extract: impl Fn(&R, &mut dyn FnMut()) + Send + Sync + 'static,
@@ -195,6 +682,14 @@ impl WorldExtension for App {
         self.world_mut().save::<T, S>(serializer)
     }
 
+    fn save_filtered<T: BatchSerialization, S: Serializer>(
+        &mut self,
+        serializer: S,
+        subset: impl EntitySubset,
+    ) -> Result<S::Ok, S::Error> {
+        self.world_mut().save_filtered::<T, S>(serializer, subset)
+    }
+
     fn load<'de, T: BatchSerialization, D: Deserializer<'de>>(
         &mut self,
         deserializer: D,
@@ -202,6 +697,136 @@ impl WorldExtension for App {
         self.world_mut().load::<T, D>(deserializer)
     }
 
+    fn try_load<'de, T: BatchSerialization, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<(), LoadError> {
+        self.world_mut().try_load::<T, D>(deserializer)
+    }
+
+    fn load_lenient<'de, T: BatchSerialization, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<Vec<LoadError>, D::Error> {
+        self.world_mut().load_lenient::<T, D>(deserializer)
+    }
+
+    fn save_to_bytes<T: BatchSerialization>(
+        &mut self,
+        format: impl SaveFormat,
+    ) -> Result<Vec<u8>, SaveFileError> {
+        self.world_mut().save_to_bytes::<T>(format)
+    }
+
+    fn save_parallel<T: BevyObject>(
+        &mut self,
+        format: impl ParallelFormat,
+    ) -> Result<Vec<u8>, SaveFileError> {
+        self.world_mut().save_parallel::<T>(format)
+    }
+
+    fn load_parallel<T: OwnedObject>(
+        &mut self,
+        bytes: &[u8],
+        format: impl ParallelFormat,
+    ) -> Result<(), LoadError> {
+        self.world_mut().load_parallel::<T>(bytes, format)
+    }
+
+    fn load_from_bytes<T: BatchSerialization>(
+        &mut self,
+        bytes: &[u8],
+        format: impl SaveFormat,
+    ) -> Result<(), LoadError> {
+        self.world_mut().load_from_bytes::<T>(bytes, format)
+    }
+
+    fn save_to_path<T: BatchSerialization>(
+        &mut self,
+        path: impl AsRef<Path>,
+        format: impl SaveFormat,
+    ) -> Result<(), SaveFileError> {
+        self.world_mut().save_to_path::<T>(path, format)
+    }
+
+    fn load_from_path<T: BatchSerialization>(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<(), LoadError> {
+        self.world_mut().load_from_path::<T>(path)
+    }
+
+    fn load_enveloped<T: BatchSerialization>(
+        &mut self,
+        bytes: &[u8],
+        format: impl SaveFormat,
+    ) -> Result<(), EnvelopeError> {
+        self.world_mut().load_enveloped::<T>(bytes, format)
+    }
+
+    fn load_merge<'de, T: BevyObject, K: Component + Eq + Hash + Clone, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+        unmatched: Unmatched,
+    ) -> Result<(), D::Error> {
+        self.world_mut()
+            .load_merge::<T, K, D>(deserializer, unmatched)
+    }
+
+    fn delta_baseline<T: BevyObject, K: Component + Eq + Hash + Clone>(
+        &mut self,
+    ) -> DeltaBaseline<K> {
+        self.world_mut().delta_baseline::<T, K>()
+    }
+
+    fn save_delta<T: BevyObject, K: Component + Eq + Hash + Clone + Serialize, S: Serializer>(
+        &mut self,
+        baseline: &DeltaBaseline<K>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.world_mut().save_delta::<T, K, S>(baseline, serializer)
+    }
+
+    fn load_delta<
+        'de,
+        T: BevyObject,
+        K: Component + Eq + Hash + Clone + DeserializeOwned,
+        D: Deserializer<'de>,
+    >(
+        &mut self,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        self.world_mut().load_delta::<T, K, D>(deserializer)
+    }
+
+    fn save_versioned<T: BatchSerialization, S: Serializer>(
+        &mut self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.world_mut().save_versioned::<T, S>(serializer)
+    }
+
+    fn load_versioned<'de, T: BatchSerialization, D: Deserializer<'de>>(
+        &mut self,
+        deserializer: D,
+    ) -> Result<(), D::Error> {
+        self.world_mut().load_versioned::<T, D>(deserializer)
+    }
+
+    fn diff<T: BatchSerialization>(
+        &mut self,
+        other: &mut World,
+    ) -> Result<WorldDiff, value::Error> {
+        self.world_mut().diff::<T>(other)
+    }
+
+    fn diff_save<T: BatchSerialization>(
+        &mut self,
+        save: &Value,
+    ) -> Result<WorldDiff, value::Error> {
+        self.world_mut().diff_save::<T>(save)
+    }
+
     fn serialize_lens<S: BatchSerialization>(&mut self) -> SerializeLens<'_, S> {
         self.world_mut().serialize_lens()
     }
@@ -214,6 +839,10 @@ impl WorldExtension for App {
         self.world_mut().despawn_bound_objects::<T>()
     }
 
+    fn schema<T: BatchSerialization>(&self) -> RootSchema {
+        self.world().schema::<T>()
+    }
+
     fn register_typetag<A: ErasedObject, B: Into<A> + TypePath + DeserializeOwned>(&mut self) {
         self.world_mut().register_typetag::<A, B>()
     }
@@ -225,6 +854,33 @@ impl WorldExtension for App {
         self.world_mut().register_typetag_by_name::<A, B>(name)
     }
 
+    fn register_dynamic_component<C: Component + Serialize + DeserializeOwned + TypePath>(
+        &mut self,
+    ) {
+        self.world_mut().register_dynamic_component::<C>()
+    }
+
+    fn register_dynamic_object(&mut self, name: &str, layout: DynamicLayout) {
+        self.world_mut().register_dynamic_object(name, layout)
+    }
+
+    fn register_migration(
+        &mut self,
+        name: &str,
+        from: u32,
+        migration: impl Fn(&mut Value) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.world_mut().register_migration(name, from, migration)
+    }
+
+    fn register_migration_alias(&mut self, old: &str, new: &str) {
+        self.world_mut().register_migration_alias(old, new)
+    }
+
+    fn register_rollback<R: Resource + Clone>(&mut self) {
+        self.world_mut().register_rollback::<R>()
+    }
+
     fn register_serialize_resource_cx<R: Resource>(
         &mut self,
         extract: impl Fn(&R, &mut dyn FnMut()) + Send + Sync + 'static,
@@ -289,3 +945,77 @@ impl Default for RegisteredExtractions {
         }
     }
 }
+
+/// Prefix an error with its path if not empty.
+pub(crate) fn located_error<E: serde::de::Error>((path, e): (ErrorPath, E)) -> E {
+    if path.is_empty() {
+        e
+    } else {
+        serde::de::Error::custom(LoadError::new(path, e))
+    }
+}
+
+/// Run `f` in a `save` context.
+pub(crate) fn save_scope<T>(world: &mut World, f: impl FnOnce(&mut World) -> T) -> T {
+    world.init_resource::<RegisteredExtractions>();
+    let mut f = Some(f);
+    let mut result = None;
+
+    let mut handles = Default::default();
+    let mut entities = Default::default();
+    SER_REUSABLE_HANDLES.set(&mut handles, || {
+        SER_ENTITY_IDS.set(&mut entities, || {
+            world.resource_scope::<RegisteredExtractions, _>(|world, extractions| {
+                (extractions.ser)(world, &mut |world| {
+                    result = Some((f.take().unwrap())(world))
+                })
+            });
+        })
+    });
+    result.unwrap()
+}
+
+/// Run `load` and return the path of the first error.
+fn load_located<'de, T: BatchSerialization, D: Deserializer<'de>>(
+    world: &mut World,
+    deserializer: D,
+) -> Result<(), (ErrorPath, D::Error)> {
+    // Discard the zst.
+    load_located_with(world, deserializer, |de| T::De::deserialize(de).map(|_| ()))
+}
+
+/// Run `f` in a `load` context and return the path of the first error.
+pub(crate) fn load_located_with<'de, D: Deserializer<'de>, T>(
+    world: &mut World,
+    deserializer: D,
+    f: impl FnOnce(D) -> Result<T, D::Error>,
+) -> Result<T, (ErrorPath, D::Error)> {
+    world.init_resource::<RegisteredExtractions>();
+    transaction::scope(world, |world| {
+        let mut deserializer = Some(deserializer);
+        let mut f = Some(f);
+        let mut result = None;
+
+        let mut handles = Default::default();
+        let mut entities = EntityMap::default();
+        let mut path = PathStack::default();
+        DE_REUSABLE_HANDLES.set(&mut handles, || {
+            DE_ENTITY_MAP.set(&mut entities, || {
+                ERROR_PATH.set(&mut path, || {
+                    world.resource_scope::<RegisteredExtractions, _>(|world, extractions| {
+                        (extractions.de)(world, &mut |world| {
+                            result = Some(ScopeUtils::deserialize_scope(world, || {
+                                (f.take().unwrap())(deserializer.take().unwrap())
+                            }))
+                        })
+                    });
+                })
+            })
+        });
+        let result = result.unwrap().map_err(|e| (path.take_failed(), e));
+        let mapped = entities
+            .apply(world)
+            .map_err(|e| (ErrorPath::default(), serde::de::Error::custom(e)));
+        result.and_then(|value| mapped.map(|_| value))
+    })
+}
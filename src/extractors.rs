@@ -1,4 +1,5 @@
-use crate::{BevyObject, BindProject, BindProjectQuery, MappedSerializer, ZstInit};
+use crate::schema::{self, Schema};
+use crate::{BevyObject, BindProject, BindProjectQuery, MappedSerializer, ZstInit, transaction};
 use bevy::ecs::{
     query::{QueryFilter, With},
     resource::Resource,
@@ -94,16 +95,9 @@ type DummyDeserializer = serde::de::value::BoolDeserializer<serde::de::value::Er
 /// To make `#[serde(default)]` work.
 impl<T: Component + FromWorld> Default for DefaultInit<T> {
     fn default() -> Self {
-        let Ok(entity) = DeUtils::current_entity::<DummyDeserializer>() else {
-            return Self(PhantomData);
-        };
-        let _ = DeUtils::with_world_mut::<DummyDeserializer, _>(|world| {
-            let item = T::from_world(world);
-            let Ok(mut entity) = world.get_entity_mut(entity) else {
-                return;
-            };
-            entity.insert(item);
-        });
+        if let Ok(item) = DeUtils::with_world_mut::<DummyDeserializer, _>(T::from_world) {
+            let _ = crate::entity::insert::<T, DummyDeserializer>(item);
+        }
         Self(PhantomData)
     }
 }
@@ -134,8 +128,11 @@ impl<'de, T: Component + FromWorld> Deserialize<'de> for DefaultInit<T> {
         D: serde::Deserializer<'de>,
     {
         <() as Deserialize>::deserialize(deserializer)?;
+        if let Some(this) = schema::provided(|| Schema::Null) {
+            return Ok(this);
+        }
         let item = DeUtils::with_world_mut::<D, _>(T::from_world)?;
-        DeUtils::insert::<D>(item)?;
+        crate::entity::insert::<T, D>(item)?;
         Ok(Self(PhantomData))
     }
 }
@@ -230,8 +227,12 @@ impl<'de, T: Component, C: MappedSerializer<T>> Deserialize<'de> for AdaptedComp
     where
         D: serde::Deserializer<'de>,
     {
-        let component = C::deserialize(deserializer)?;
-        DeUtils::insert::<D>(component)?;
+        if let Some(this) =
+            schema::provided(|| schema::trace_with(|tracer| C::deserialize(tracer).map(|_: T| ())))
+        {
+            return Ok(this);
+        }
+        crate::entity::insert_component::<T, D>(|| C::deserialize(deserializer))?;
         Ok(ZstInit::init())
     }
 }
@@ -264,7 +265,7 @@ impl<'de, T: Resource + Deserialize<'de>> Deserialize<'de> for SerializeResource
     {
         let resource = T::deserialize(deserializer)?;
         DeUtils::with_world_mut::<D, _>(|world| {
-            world.insert_resource(resource);
+            transaction::replace_resource(world, resource);
         })?;
         Ok(Self(PhantomData))
     }
@@ -298,7 +299,7 @@ impl<'de, T: Deserialize<'de> + 'static> Deserialize<'de> for SerializeNonSend<T
     {
         let resource = T::deserialize(deserializer)?;
         DeUtils::with_world_mut::<D, _>(|world| {
-            world.insert_non_send(resource);
+            transaction::replace_non_send(world, resource);
         })?;
         Ok(Self(PhantomData))
     }
@@ -332,6 +333,8 @@ impl<'de, T: FreelyMutableState + Deserialize<'de>> Deserialize<'de> for Seriali
     {
         let new_state = T::deserialize(deserializer)?;
         DeUtils::with_resource_mut::<NextState<T>, D, _>(|mut state| {
+            let prev = (*state).clone();
+            transaction::record(move |world| world.insert_resource(prev));
             state.set(new_state);
         })?;
         Ok(Self(PhantomData))
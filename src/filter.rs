@@ -1,5 +1,13 @@
+use bevy::ecs::entity::Entity;
 use bevy::ecs::query::{Or, QueryFilter, With, Without};
+use bevy::ecs::world::World;
 use bevy::ecs::{component::Component, world::EntityRef};
+use rustc_hash::FxHashSet;
+use scoped_tls_hkt::scoped_thread_local;
+
+scoped_thread_local!(
+    static ROOT_FILTER: FxHashSet<Entity>
+);
 
 /// A subset of [`QueryFilter`] that works on [`EntityRef`].
 /// Supports tuples, [`With`], [`Without`] and [`Or`].
@@ -56,3 +64,62 @@ macro_rules! impl_tuple {
 }
 
 impl_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+
+/// A subset of root entities to save with `save_filtered`,
+/// either a predicate on [`EntityRef`] or a list of entities.
+pub trait EntitySubset {
+    fn collect(self, world: &mut World) -> FxHashSet<Entity>;
+}
+
+impl<F> EntitySubset for F
+where
+    F: Fn(&EntityRef) -> bool,
+{
+    fn collect(self, world: &mut World) -> FxHashSet<Entity> {
+        let mut query = world.query::<EntityRef>();
+        query
+            .iter(world)
+            .filter(|entity| self(entity))
+            .map(|entity| entity.id())
+            .collect()
+    }
+}
+
+impl EntitySubset for &[Entity] {
+    fn collect(self, _: &mut World) -> FxHashSet<Entity> {
+        self.iter().copied().collect()
+    }
+}
+
+impl<const N: usize> EntitySubset for [Entity; N] {
+    fn collect(self, _: &mut World) -> FxHashSet<Entity> {
+        self.into_iter().collect()
+    }
+}
+
+impl EntitySubset for Vec<Entity> {
+    fn collect(self, _: &mut World) -> FxHashSet<Entity> {
+        self.into_iter().collect()
+    }
+}
+
+impl EntitySubset for FxHashSet<Entity> {
+    fn collect(self, _: &mut World) -> FxHashSet<Entity> {
+        self
+    }
+}
+
+/// Only save root objects in `entities` while running `f`.
+pub(crate) fn filter_roots<T>(entities: &FxHashSet<Entity>, f: impl FnOnce() -> T) -> T {
+    ROOT_FILTER.set(entities, f)
+}
+
+/// Returns true if root objects are filtered by `save_filtered`.
+pub(crate) fn is_filtered() -> bool {
+    ROOT_FILTER.is_set()
+}
+
+/// Returns true if a root object should be saved.
+pub(crate) fn is_selected(entity: Entity) -> bool {
+    !ROOT_FILTER.is_set() || ROOT_FILTER.with(|entities| entities.contains(&entity))
+}
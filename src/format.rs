@@ -0,0 +1,366 @@
+//! Module for serde format backends.
+//!
+//! [`SaveFormat`] erases a serde format so saves can be written to and read from bytes
+//! without naming its serializer and deserializer types.
+//!
+//! Backends are enabled by features:
+//!
+//! | feature    | format       | extensions          |
+//! |------------|--------------|---------------------|
+//! | `json`     | [`Json`]     | `json`              |
+//! | `ron`      | [`Ron`]      | `ron`               |
+//! | `postcard` | [`Postcard`] | `postcard`, `bin`   |
+//!
+//! ```
+//! world.save_to_path::<SaveData>("saves/1.ron", Ron)?;
+//! // Format is detected from the file extension.
+//! world.load_from_path::<SaveData>("saves/1.ron")?;
+//! ```
+use std::error::Error;
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::value::Value;
+
+/// Boxed error of a [`SaveFormat`].
+pub type FormatError = Box<dyn Error + Send + Sync>;
+
+/// Callback of [`SaveFormat::deserialize`].
+pub type DeserializeFn<'t> = dyn for<'de> FnMut(&mut dyn erased_serde::Deserializer<'de>) -> Result<(), erased_serde::Error>
+    + 't;
+
+/// A type erased serde format.
+pub trait SaveFormat: Send + Sync + 'static {
+    /// File extensions handled by this format, without the leading dot.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Identifier of this format, written in the header of an [`Enveloped`](crate::envelope::Enveloped) save.
+    ///
+    /// By default this is the first extension.
+    fn id(&self) -> &'static str {
+        self.extensions().first().copied().unwrap_or_default()
+    }
+
+    /// Serialize a value into bytes.
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError>;
+
+    /// Create a deserializer from bytes and call `f` on it.
+    ///
+    /// Implementations should check for trailing bytes after `f` returns.
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError>;
+
+    /// Parse bytes into a [`Value`], requires a self describing format.
+    fn parse(&self, bytes: &[u8]) -> Result<Value, FormatError> {
+        let mut value = Value::Unit;
+        self.deserialize(bytes, &mut |deserializer| {
+            value = Value::deserialize(deserializer)?;
+            Ok(())
+        })?;
+        Ok(value)
+    }
+
+    /// Write a [`Value`] as bytes.
+    fn write(&self, value: &Value) -> Result<Vec<u8>, FormatError> {
+        self.serialize(value)
+    }
+}
+
+impl<F: SaveFormat + ?Sized> SaveFormat for &'static F {
+    fn extensions(&self) -> &'static [&'static str] {
+        F::extensions(self)
+    }
+
+    fn id(&self) -> &'static str {
+        F::id(self)
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        F::serialize(self, value)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        F::deserialize(self, bytes, f)
+    }
+}
+
+/// A [`SaveFormat`] that can serialize elements of a sequence independently,
+/// allowing `save_parallel` to serialize chunks of a sequence on multiple threads.
+pub trait ParallelFormat: SaveFormat {
+    /// Serialize an element of a sequence, appending it to `buffer`.
+    ///
+    /// `buffer` contains previous elements of the same chunk.
+    fn serialize_element(
+        &self,
+        value: &dyn erased_serde::Serialize,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), FormatError>;
+
+    /// Join chunks of serialized elements into a sequence of `len` elements.
+    fn join_seq(&self, len: usize, chunks: Vec<Vec<u8>>) -> Vec<u8>;
+
+    /// Split a serialized sequence into its serialized elements,
+    /// each element must be deserializable with [`SaveFormat::deserialize`].
+    ///
+    /// Returns `None` if not supported, `load_parallel` deserializes
+    /// the sequence on a single thread in that case.
+    #[allow(unused_variables)]
+    fn split_seq<'t>(&self, bytes: &'t [u8]) -> Option<Result<Vec<&'t [u8]>, FormatError>> {
+        None
+    }
+}
+
+impl<F: ParallelFormat + ?Sized> ParallelFormat for &'static F {
+    fn serialize_element(
+        &self,
+        value: &dyn erased_serde::Serialize,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), FormatError> {
+        F::serialize_element(self, value, buffer)
+    }
+
+    fn join_seq(&self, len: usize, chunks: Vec<Vec<u8>>) -> Vec<u8> {
+        F::join_seq(self, len, chunks)
+    }
+
+    fn split_seq<'t>(&self, bytes: &'t [u8]) -> Option<Result<Vec<&'t [u8]>, FormatError>> {
+        F::split_seq(self, bytes)
+    }
+}
+
+/// Returns formats enabled by features.
+pub fn enabled_formats() -> &'static [&'static dyn SaveFormat] {
+    &[
+        #[cfg(feature = "json")]
+        &Json,
+        #[cfg(feature = "ron")]
+        &Ron,
+        #[cfg(feature = "postcard")]
+        &Postcard,
+    ]
+}
+
+/// Find an enabled format by the extension of a path.
+pub fn format_from_path(path: &Path) -> Option<&'static dyn SaveFormat> {
+    let extension = path.extension()?.to_str()?;
+    enabled_formats().iter().copied().find(|format| {
+        format
+            .extensions()
+            .iter()
+            .any(|ext| ext.eq_ignore_ascii_case(extension))
+    })
+}
+
+/// Write to a temporary file in the same directory, then rename it to `path`.
+///
+/// This ensures `path` contains either the old or the new save if interrupted.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut temp = OsString::from(path.as_os_str());
+    temp.push(".tmp");
+    let temp = PathBuf::from(temp);
+    let result = (|| {
+        let mut file = std::fs::File::create(&temp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        std::fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// The `json` format via `serde_json`, pretty printed.
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+#[derive(Debug, Clone, Copy, Default, bevy::reflect::TypePath)]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl SaveFormat for Json {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Ok(serde_json::to_vec_pretty(value)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        f(&mut <dyn erased_serde::Deserializer>::erase(
+            &mut deserializer,
+        ))?;
+        deserializer.end()?;
+        Ok(())
+    }
+}
+
+/// Elements are written compactly.
+#[cfg(feature = "json")]
+impl ParallelFormat for Json {
+    fn serialize_element(
+        &self,
+        value: &dyn erased_serde::Serialize,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), FormatError> {
+        if !buffer.is_empty() {
+            buffer.push(b',');
+        }
+        serde_json::to_writer(buffer, value)?;
+        Ok(())
+    }
+
+    fn join_seq(&self, _: usize, chunks: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut result = Vec::with_capacity(chunks.iter().map(Vec::len).sum::<usize>() + 2);
+        result.push(b'[');
+        for chunk in chunks.into_iter().filter(|chunk| !chunk.is_empty()) {
+            if result.len() > 1 {
+                result.push(b',');
+            }
+            result.extend(chunk);
+        }
+        result.push(b']');
+        result
+    }
+
+    fn split_seq<'t>(&self, bytes: &'t [u8]) -> Option<Result<Vec<&'t [u8]>, FormatError>> {
+        Some(split_json_array(bytes))
+    }
+}
+
+/// Split a json array into its elements without parsing them.
+#[cfg(feature = "json")]
+fn split_json_array(bytes: &[u8]) -> Result<Vec<&[u8]>, FormatError> {
+    let trimmed = bytes.trim_ascii();
+    let Some(inner) = trimmed
+        .strip_prefix(b"[")
+        .and_then(|inner| inner.strip_suffix(b"]"))
+    else {
+        return Err("expected a json array".into());
+    };
+    let mut result = Vec::new();
+    if inner.trim_ascii().is_empty() {
+        return Ok(result);
+    }
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, byte) in inner.iter().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or("unbalanced brackets in json array")?
+            }
+            b',' if depth == 0 => {
+                result.push(inner[start..i].trim_ascii());
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+    if depth != 0 || in_string {
+        return Err("unterminated json array".into());
+    }
+    result.push(inner[start..].trim_ascii());
+    Ok(result)
+}
+
+/// The `ron` format, pretty printed.
+#[cfg(feature = "ron")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ron")))]
+#[derive(Debug, Clone, Copy, Default, bevy::reflect::TypePath)]
+pub struct Ron;
+
+#[cfg(feature = "ron")]
+impl SaveFormat for Ron {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ron"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Ok(ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?.into_bytes())
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let mut deserializer = ron::Deserializer::from_bytes(bytes)?;
+        f(&mut <dyn erased_serde::Deserializer>::erase(
+            &mut deserializer,
+        ))?;
+        deserializer.end()?;
+        Ok(())
+    }
+}
+
+/// The `postcard` binary format.
+///
+/// This format is not self describing and cannot be used in a
+/// [`SaveFile`](crate::save_file::SaveFile) or with migrations.
+#[cfg(feature = "postcard")]
+#[cfg_attr(docsrs, doc(cfg(feature = "postcard")))]
+#[derive(Debug, Clone, Copy, Default, bevy::reflect::TypePath)]
+pub struct Postcard;
+
+#[cfg(feature = "postcard")]
+impl SaveFormat for Postcard {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["postcard", "bin"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Ok(postcard::to_allocvec(value)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let mut deserializer = postcard::Deserializer::from_bytes(bytes);
+        f(&mut <dyn erased_serde::Deserializer>::erase(
+            &mut deserializer,
+        ))?;
+        if !deserializer.finalize()?.is_empty() {
+            return Err("trailing bytes after postcard save.".into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "postcard")]
+impl ParallelFormat for Postcard {
+    fn serialize_element(
+        &self,
+        value: &dyn erased_serde::Serialize,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), FormatError> {
+        buffer.extend(postcard::to_allocvec(value)?);
+        Ok(())
+    }
+
+    fn join_seq(&self, len: usize, chunks: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut result = Vec::with_capacity(chunks.iter().map(Vec::len).sum::<usize>() + 10);
+        // Length of a sequence is a `varint`.
+        let mut len = len as u64;
+        while len >= 0x80 {
+            result.push(len as u8 | 0x80);
+            len >>= 7;
+        }
+        result.push(len as u8);
+        for chunk in chunks {
+            result.extend(chunk);
+        }
+        result
+    }
+}
@@ -12,7 +12,7 @@ use serde::Deserializer;
 use serde::Serialize;
 use serde::Serializer;
 
-use crate::impl_with_notation_newtype;
+use crate::{impl_with_notation_newtype, transaction};
 
 /// A key to a value in an [`Interner`] resource.
 pub trait InterningKey: Sized + 'static {
@@ -20,6 +20,9 @@ pub trait InterningKey: Sized + 'static {
 }
 
 /// A [`Resource`] that holds a pool of values accessible by a [`InterningKey`].
+///
+/// Values added by a failed `load` are kept unless the interner is registered with
+/// [`WorldExtension::register_rollback`](crate::WorldExtension::register_rollback).
 pub trait Interner<Key>: Resource<Mutability = Mutable> {
     type Error: std::error::Error;
     type ValueRef<'t>: Serialize;
@@ -60,6 +63,7 @@ impl<T: InterningKey> Serialize for Interned<T> {
 impl<'de, T: InterningKey> Deserialize<'de> for Interned<T> {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         let value = <<T::Interner as Interner<T>>::Value<'de>>::deserialize(deserializer)?;
+        DeUtils::with_world_mut::<D, _>(transaction::record_resource::<T::Interner>)?;
         DeUtils::with_resource_mut::<T::Interner, D, _>(|mut interner| match interner.add(value) {
             Ok(value) => Ok(Interned(value)),
             Err(err) => Err(DeUtils::error::<D>(err)),
@@ -0,0 +1,39 @@
+use bevy_serde_lens_core::DeUtils;
+use scoped_tls_hkt::scoped_thread_local;
+use serde::{Deserialize, Deserializer, de::DeserializeOwned};
+
+use crate::error::{LoadError, take_error_path};
+use crate::transaction;
+use crate::value::Value;
+
+scoped_thread_local!(
+    pub(crate) static mut DIAGNOSTICS: Vec<LoadError>
+);
+
+/// Deserialize an element of `Root`, `ChildVec` or `ChildMap`.
+///
+/// In a `load_lenient` scope, the element is buffered as a [`Value`] first,
+/// if deserialization fails, changes are rolled back, the error is reported
+/// and `None` is returned.
+pub(crate) struct Lenient<T>(pub Option<T>);
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Lenient<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !DIAGNOSTICS.is_set() {
+            return T::deserialize(deserializer).map(|value| Lenient(Some(value)));
+        }
+        let value = Value::deserialize(deserializer)?;
+        let checkpoint = transaction::checkpoint();
+        match T::deserialize(value) {
+            Ok(value) => Ok(Lenient(Some(value))),
+            Err(err) => {
+                DeUtils::with_world_mut::<D, _>(|world| {
+                    transaction::rollback_to(world, checkpoint)
+                })?;
+                let err = LoadError::new(take_error_path(), err);
+                DIAGNOSTICS.with(|diagnostics| diagnostics.push(err));
+                Ok(Lenient(None))
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::collapsible_if)]
 #![allow(clippy::collapsible_else_if)]
+use bevy::ecs::bundle::{Bundle, NoBundleEffect};
 use bevy::ecs::component::Component;
 use bevy::ecs::query::{QueryData, QueryFilter};
 use bevy::ecs::world::EntityRef;
@@ -13,19 +14,41 @@ pub use children::{Child, ChildVec, ChildrenLike};
 mod batch;
 mod childmap;
 pub use childmap::{ChildMap, ChildMapLike};
+mod commands;
+pub use commands::{CommandsExtension, LoadCompleted, RequestId, SaveCompleted, SaveTarget};
 mod extensions;
+mod lenient;
+mod merge;
+mod parallel;
 mod root;
+mod transaction;
 pub use batch::{BatchSerialization, Join, SerializeWorld};
 pub use extensions::{InWorld, SerializeLens, WorldExtension};
+pub use merge::Unmatched;
 pub use root::RootObject;
 mod adjacent;
 pub use adjacent::{Adjacent, SerializeAdjacent};
 pub mod asset;
+pub mod chunk;
+pub mod delta;
+pub mod diff;
+pub mod dynamic;
+pub mod entity;
+pub mod envelope;
+pub mod error;
 mod filter;
+pub mod format;
 pub mod interning;
+pub mod migration;
+pub mod reflect;
+pub mod replication;
+pub mod save_file;
+pub mod schema;
 pub mod typetagged;
 mod util;
-pub use filter::EntityFilter;
+pub mod value;
+pub use filter::{EntityFilter, EntitySubset};
+use schema::Schema;
 pub use util::*;
 #[cfg(any(feature = "linkme", doc))]
 #[cfg_attr(docsrs, doc(cfg(feature = "linkme")))]
@@ -39,7 +62,7 @@ use bevy::ecs::hierarchy::Children;
 #[doc(hidden)]
 pub use bevy::ecs::{
     entity::Entity,
-    query::With,
+    query::{Or, With},
     world::{EntityWorldMut, World},
 };
 #[doc(hidden)]
@@ -47,7 +70,8 @@ pub use bevy::reflect::TypePath;
 #[doc(hidden)]
 pub use serde;
 #[allow(unused)]
-use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde::{Deserialize, Serialize, Serializer, de::DeserializeOwned};
+use std::cell::Cell;
 
 pub use bevy_serde_lens_core::{DeUtils, SerUtils};
 #[cfg(feature = "derive")]
@@ -68,6 +92,25 @@ pub type Item<'t, T> =
 pub type BindItem<'t, T> =
     <<<T as BindProjectQuery>::Data as QueryData>::ReadOnly as QueryData>::Item<'t, 't>;
 
+/// Serialize a query item with [`BevyObject::into_ser`], used by `flatten` in `query` mode.
+#[doc(hidden)]
+pub struct FlattenItem<'t, T: BevyObject>(Cell<Option<Item<'t, T>>>);
+
+impl<'t, T: BevyObject> FlattenItem<'t, T> {
+    pub fn new(item: Item<'t, T>) -> Self {
+        Self(Cell::new(Some(item)))
+    }
+}
+
+impl<T: BevyObject> Serialize for FlattenItem<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.take() {
+            Some(item) => T::into_ser(item).serialize(serializer),
+            None => Err(serde::ser::Error::custom("Item already serialized.")),
+        }
+    }
+}
+
 /// Associate a [`BevyObject`] to a [`EntityFilter`], usually a component as `With<Component>`.
 ///
 /// This means `world.save::<T>()` will try to serialize all entities that satisfies the filter.
@@ -112,6 +155,11 @@ pub trait BevyObject {
     /// Convert `Data` to a serializable, must specify if `IS_QUERY`.
     #[allow(unused_variables)]
     fn into_ser(query_data: Item<'_, Self>) -> impl Serialize {}
+
+    /// Layout of the serialized object, default is [`Schema::Any`].
+    fn schema() -> Schema {
+        Schema::Any
+    }
 }
 
 impl<T> BevyObject for T
@@ -131,6 +179,35 @@ where
     fn into_ser(query_data: Item<'_, Self>) -> impl Serialize {
         query_data
     }
+
+    fn schema() -> Schema {
+        schema::trace::<T>()
+    }
+}
+
+/// A [`BevyObject`] that can be deserialized without world access
+/// as an owned value, then spawned as a [`Bundle`].
+///
+/// Used by `load_parallel`, can be derived with `#[bevy_object(parallel)]`.
+pub trait OwnedObject: BevyObject {
+    /// Owned value with the same layout as [`BevyObject::Object`].
+    type Owned: DeserializeOwned + Send + 'static;
+    /// Bundle spawned from the owned value.
+    type Bundle: Bundle<Effect: NoBundleEffect>;
+
+    fn into_bundle(owned: Self::Owned) -> Self::Bundle;
+}
+
+impl<T> OwnedObject for T
+where
+    T: Component + Serialize + DeserializeOwned + TypePath,
+{
+    type Owned = T;
+    type Bundle = T;
+
+    fn into_bundle(owned: Self::Owned) -> Self::Bundle {
+        owned
+    }
 }
 
 /// Make a type usable in the [`BevyObject`] macro.
@@ -0,0 +1,158 @@
+use std::any::{Any, TypeId};
+use std::hash::Hash;
+
+use bevy::ecs::{component::Component, entity::Entity, hierarchy::Children, world::World};
+use bevy_serde_lens_core::{DeUtils, ScopeUtils};
+use rustc_hash::FxHashMap;
+use scoped_tls_hkt::scoped_thread_local;
+use serde::Deserializer;
+
+use crate::transaction;
+
+#[allow(unused)]
+use crate::WorldExtension;
+
+scoped_thread_local!(
+    pub(crate) static mut MERGE_SCOPE: MergeScope
+);
+
+/// What to do with existing entities not present in a
+/// [`WorldExtension::load_merge`] call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Unmatched {
+    /// Keep the entities.
+    #[default]
+    Keep,
+    /// Despawn the entities recursively.
+    Despawn,
+}
+
+type LookupFn = fn(&mut dyn Any, &dyn Any) -> Option<Entity>;
+
+/// Moves a change from the spawned root entity to the matched entity.
+type MoveFn = Box<dyn FnOnce(&mut World, Entity, Entity)>;
+
+/// Existing entities by key in a `load_merge` call.
+pub(crate) struct MergeScope {
+    key: TypeId,
+    candidates: Box<dyn Any>,
+    lookup: LookupFn,
+    depth: usize,
+    redirect: Option<Entity>,
+    /// Changes made to the root entity before its key is found.
+    moves: Vec<MoveFn>,
+}
+
+fn lookup<K: Eq + Hash + 'static>(candidates: &mut dyn Any, key: &dyn Any) -> Option<Entity> {
+    let candidates = candidates.downcast_mut::<FxHashMap<K, Entity>>()?;
+    candidates.remove(key.downcast_ref::<K>()?)
+}
+
+impl MergeScope {
+    pub(crate) fn new<K: Component + Eq + Hash>(candidates: FxHashMap<K, Entity>) -> Self {
+        MergeScope {
+            key: TypeId::of::<K>(),
+            candidates: Box::new(candidates),
+            lookup: lookup::<K>,
+            depth: 0,
+            redirect: None,
+            moves: Vec::new(),
+        }
+    }
+
+    /// Existing entities not matched during the load.
+    pub(crate) fn unmatched<K: Component + Eq + Hash>(self) -> Vec<Entity> {
+        match self.candidates.downcast::<FxHashMap<K, Entity>>() {
+            Ok(candidates) => candidates.into_values().collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// Run a root object's deserialization, returns the matched entity if redirected.
+pub(crate) fn root_scope<T>(f: impl FnOnce() -> T) -> (T, Option<Entity>) {
+    if !MERGE_SCOPE.is_set() {
+        return (f(), None);
+    }
+    MERGE_SCOPE.with(|scope| scope.depth += 1);
+    let result = f();
+    let redirect = MERGE_SCOPE.with(|scope| {
+        scope.depth -= 1;
+        if scope.depth == 0 {
+            scope.moves.clear();
+            scope.redirect.take()
+        } else {
+            None
+        }
+    });
+    (result, redirect)
+}
+
+/// Record a change to the current entity, replayed on the matched entity
+/// if the key of the root object is found later.
+pub(crate) fn track(f: impl FnOnce(&mut World, Entity, Entity) + 'static) {
+    if !MERGE_SCOPE.is_set() {
+        return;
+    }
+    MERGE_SCOPE.with(|scope| {
+        if scope.depth == 1 && scope.redirect.is_none() {
+            scope.moves.push(Box::new(f));
+        }
+    });
+}
+
+/// Move a component to another entity, recording the previous value.
+pub(crate) fn move_component<T: Component>(world: &mut World, from: Entity, to: Entity) {
+    let Some(component) = world
+        .get_entity_mut(from)
+        .ok()
+        .and_then(|mut entity| entity.take::<T>())
+    else {
+        return;
+    };
+    transaction::record_replace::<T>(world, to);
+    if let Ok(mut entity) = world.get_entity_mut(to) {
+        entity.insert(component);
+    }
+}
+
+/// If `component` is the key of a root object and matches an existing entity,
+/// make that entity the current entity.
+///
+/// Fields deserialized before the key are moved to the existing entity.
+pub(crate) fn redirect<'de, T: Component, D: Deserializer<'de>>(
+    component: &T,
+) -> Result<(), D::Error> {
+    if !MERGE_SCOPE.is_set() {
+        return Ok(());
+    }
+    MERGE_SCOPE.with(|scope| {
+        if scope.depth != 1 || scope.redirect.is_some() || scope.key != TypeId::of::<T>() {
+            return Ok(());
+        }
+        let Some(existing) = (scope.lookup)(scope.candidates.as_mut(), component) else {
+            return Ok(());
+        };
+        let current = DeUtils::current_entity::<D>()?;
+        let moves = std::mem::take(&mut scope.moves);
+        DeUtils::with_world_mut::<D, _>(|world| {
+            // Children are deserialized again, old children are despawned once `load` succeeds.
+            let children = world
+                .get::<Children>(existing)
+                .map(|children| children.to_vec())
+                .unwrap_or_default();
+            transaction::on_commit(world, move |world| {
+                for child in children {
+                    let _ = world.despawn(child);
+                }
+            });
+            for f in moves {
+                f(world, current, existing);
+            }
+        })?;
+        crate::entity::redirect(current, existing);
+        scope.redirect = Some(existing);
+        ScopeUtils::set_current_entity(existing);
+        Ok(())
+    })
+}
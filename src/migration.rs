@@ -0,0 +1,159 @@
+//! Module for versioned saves.
+//!
+//! `save_versioned` writes a header containing the current version of each
+//! registered name alongside the data:
+//!
+//! ```json
+//! {
+//!     "versions": { "Unit": 2 },
+//!     "data": { "Unit": [..], "Weather": ".." }
+//! }
+//! ```
+//!
+//! `load_versioned` parses the data into a [`Value`] and upgrades the value of each
+//! [`BevyObject::name`] or [`SerializeWorld::name`] step by step
+//! using registered migrations before the normal `load`.
+//!
+//! ```
+//! // Version 0 to 1: `health` is renamed to `hp`.
+//! world.register_migration("Unit", 0, |value| {
+//!     for unit in value.as_seq_mut().into_iter().flatten() {
+//!         unit.rename("health", "hp");
+//!     }
+//!     Ok(())
+//! });
+//! ```
+//!
+//! Names missing from the header are assumed to be version `0`,
+//! saves without a header, like ones created by `save`, can be loaded as well.
+//!
+//! # Note
+//!
+//! Migrations require a self describing format like `json` or `ron`.
+use std::collections::BTreeMap;
+
+use bevy::ecs::resource::Resource;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::value::{self, Value};
+use crate::{BatchSerialization, SerializeLens};
+
+#[allow(unused)]
+use crate::{BevyObject, SerializeWorld};
+
+/// A migration step that upgrades a value by one version.
+pub type MigrationFn = Box<dyn Fn(&mut Value) -> Result<(), String> + Send + Sync>;
+
+/// A [`Resource`] that stores registered migrations.
+#[derive(Resource, Default)]
+pub struct Migrations {
+    steps: FxHashMap<String, BTreeMap<u32, MigrationFn>>,
+    aliases: FxHashMap<String, String>,
+}
+
+impl Migrations {
+    /// Register a migration of `name` from version `from` to `from + 1`.
+    ///
+    /// The current version of `name` is the highest `from` plus one.
+    pub fn register(
+        &mut self,
+        name: &str,
+        from: u32,
+        migration: impl Fn(&mut Value) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.steps
+            .entry(name.to_owned())
+            .or_default()
+            .insert(from, Box::new(migration));
+    }
+
+    /// Register a renamed object, values saved under `old` are loaded as `new`.
+    ///
+    /// Migrations should be registered under the new name.
+    pub fn register_alias(&mut self, old: &str, new: &str) {
+        self.aliases.insert(old.to_owned(), new.to_owned());
+    }
+
+    /// Returns the current version of `name`, `0` if no migrations are registered.
+    pub fn version(&self, name: &str) -> u32 {
+        self.steps
+            .get(name)
+            .and_then(|steps| steps.last_key_value())
+            .map(|(from, _)| from + 1)
+            .unwrap_or(0)
+    }
+
+    /// Returns the current versions of all registered names.
+    pub fn versions(&self) -> BTreeMap<String, u32> {
+        self.steps
+            .keys()
+            .map(|name| (name.clone(), self.version(name)))
+            .collect()
+    }
+
+    /// Upgrade a value of `name` from version `from` to the current version.
+    pub fn migrate(&self, name: &str, from: u32, value: &mut Value) -> Result<(), String> {
+        let current = self.version(name);
+        if from > current {
+            return Err(format!(
+                "Version {from} of {name} is newer than the supported version {current}."
+            ));
+        }
+        for version in from..current {
+            let Some(step) = self.steps.get(name).and_then(|steps| steps.get(&version)) else {
+                return Err(format!(
+                    "Missing migration of {name} from version {version}."
+                ));
+            };
+            step(value)
+                .map_err(|e| format!("Migration of {name} from version {version} failed: {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// Resolve aliases of `name` and upgrade its value using versions in a save header.
+    pub(crate) fn migrate_saved(
+        &self,
+        name: &mut String,
+        versions: &FxHashMap<String, u32>,
+        value: &mut Value,
+    ) -> Result<(), String> {
+        if let Some(new) = self.aliases.get(name.as_str()) {
+            let from = versions.get(name.as_str()).copied();
+            *name = new.clone();
+            let from = from.or_else(|| versions.get(name.as_str()).copied());
+            return self.migrate(name, from.unwrap_or(0), value);
+        }
+        let from = versions.get(name.as_str()).copied().or_else(|| {
+            self.aliases
+                .iter()
+                .filter(|(_, new)| new.as_str() == name.as_str())
+                .find_map(|(old, _)| versions.get(old).copied())
+        });
+        self.migrate(name, from.unwrap_or(0), value)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename = "Versioned", bound = "")]
+pub(crate) struct VersionedSave<'t, T: BatchSerialization> {
+    pub versions: BTreeMap<String, u32>,
+    pub data: SerializeLens<'t, T>,
+}
+
+/// Split a parsed save into its version header and data.
+///
+/// Saves without a header are treated as version `0`.
+pub(crate) fn split_header(
+    mut value: Value,
+) -> Result<(FxHashMap<String, u32>, Value), value::Error> {
+    let is_versioned = value.as_map().is_some_and(|entries| entries.len() == 2)
+        && value.get("versions").is_some()
+        && value.get("data").is_some();
+    if !is_versioned {
+        return Ok((FxHashMap::default(), value));
+    }
+    let versions = FxHashMap::deserialize(value.remove("versions").unwrap_or_default())?;
+    Ok((versions, value.remove("data").unwrap_or_default()))
+}
@@ -0,0 +1,114 @@
+use bevy::ecs::entity::Entity;
+use bevy::ecs::world::World;
+use bevy::tasks::{ComputeTaskPool, TaskPool};
+use bevy_serde_lens_core::ScopeUtils;
+use serde::de::DeserializeOwned;
+
+use crate::error::{ErrorPath, LoadError, PathSegment};
+use crate::format::{FormatError, ParallelFormat, SaveFormat};
+use crate::save_file::SaveFileError;
+use crate::{BevyObject, OwnedObject, WorldExtension};
+
+/// Number of objects serialized by each task.
+const CHUNK_SIZE: usize = 4096;
+
+/// Serialize roots of a [`BevyObject`] in chunks on the [`ComputeTaskPool`].
+///
+/// Falls back to sequential serialization if not `IS_QUERY` or if there is only one chunk.
+pub(crate) fn save_parallel<T: BevyObject>(
+    world: &mut World,
+    format: &impl ParallelFormat,
+) -> Result<Vec<u8>, SaveFileError> {
+    let mut entities = Vec::new();
+    if T::IS_QUERY {
+        let mut query = world.query_filtered::<Entity, T::Filter>();
+        entities.extend(query.iter(world));
+    }
+    if entities.len() <= CHUNK_SIZE {
+        return format
+            .serialize(&world.serialize_lens::<T>())
+            .map_err(SaveFileError::Format);
+    }
+    let mut query = world.query_filtered::<T::Data, T::Filter>();
+    query.update_archetypes(world);
+    let world: &World = world;
+    let query = &query;
+    let chunks = ComputeTaskPool::get_or_init(TaskPool::default).scope(|scope| {
+        for chunk in entities.chunks(CHUNK_SIZE) {
+            scope.spawn(async move {
+                let mut buffer = Vec::new();
+                ScopeUtils::serialize_scope(world, || {
+                    for entity in chunk {
+                        let item = query.get_manual(world, *entity)?;
+                        format.serialize_element(&T::into_ser(item), &mut buffer)?;
+                    }
+                    Ok::<_, FormatError>(buffer)
+                })
+                // Task outputs cannot contain a bare `dyn Error` under the multi threaded `TaskPool`.
+                .map_err(SaveFileError::Format)
+            });
+        }
+    });
+    let chunks = chunks.into_iter().collect::<Result<Vec<_>, _>>()?;
+    Ok(format.join_seq(entities.len(), chunks))
+}
+
+fn deserialize_owned<T: DeserializeOwned>(
+    format: &impl SaveFormat,
+    bytes: &[u8],
+) -> Result<T, FormatError> {
+    let mut result = None;
+    format.deserialize(bytes, &mut |deserializer| {
+        result = Some(T::deserialize(deserializer)?);
+        Ok(())
+    })?;
+    result.ok_or_else(|| "format did not deserialize a value".into())
+}
+
+/// Deserialize roots of an [`OwnedObject`] in chunks on the [`ComputeTaskPool`],
+/// then spawn them with [`World::spawn_batch`].
+///
+/// Falls back to deserializing on the current thread if [`ParallelFormat::split_seq`] is not supported.
+pub(crate) fn load_parallel<T: OwnedObject>(
+    world: &mut World,
+    bytes: &[u8],
+    format: &impl ParallelFormat,
+) -> Result<(), LoadError> {
+    let owned = match format.split_seq(bytes) {
+        Some(elements) => {
+            let elements = elements.map_err(|e| LoadError::new(ErrorPath::default(), e))?;
+            let chunks = ComputeTaskPool::get_or_init(TaskPool::default).scope(|scope| {
+                for (index, chunk) in elements.chunks(CHUNK_SIZE).enumerate() {
+                    scope.spawn(async move {
+                        chunk
+                            .iter()
+                            .enumerate()
+                            .map(|(i, bytes)| {
+                                deserialize_owned::<T::Owned>(format, bytes).map_err(|e| {
+                                    let path = vec![PathSegment::Index(index * CHUNK_SIZE + i)];
+                                    LoadError::new(ErrorPath(path), e)
+                                })
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                    });
+                }
+            });
+            let mut owned = Vec::with_capacity(elements.len());
+            for chunk in chunks {
+                owned.extend(chunk?);
+            }
+            owned
+        }
+        None => deserialize_owned::<Vec<T::Owned>>(format, bytes)
+            .map_err(|e| LoadError::new(ErrorPath::default(), e))?,
+    };
+    // Nothing is spawned until every object is deserialized.
+    let entities = world
+        .spawn_batch(owned.into_iter().map(T::into_bundle))
+        .collect::<Vec<_>>();
+    if let Some(mut root) = T::get_root(world) {
+        root.add_children(&entities);
+    }
+    world.flush();
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Module for serializing components with [`Reflect`] instead of serde.
+//!
+//! [`ReflectComponent`] can be used as a field of a [`BevyObject`]
+//! for components that implement [`Reflect`] but not [`Serialize`](serde::Serialize):
+//!
+//! ```
+//! #[derive(BevyObject)]
+//! struct Unit {
+//!     hp: Hp,
+//!     velocity: ReflectComponent<Velocity>,
+//! }
+//! ```
+//!
+//! Components are serialized with [`TypedReflectSerializer`] and deserialized with
+//! [`TypedReflectDeserializer`], using the [`AppTypeRegistry`] of the world in the current scope.
+//!
+//! # Note
+//!
+//! * The component must be registered in [`AppTypeRegistry`].
+//! * Cannot be used in `#[bevy_object(query)]`.
+use std::any::TypeId;
+
+use bevy::ecs::reflect::AppTypeRegistry;
+use bevy::reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
+use bevy::reflect::{FromReflect, Reflect, TypePath};
+use bevy_serde_lens_core::{DeUtils, SerUtils};
+use serde::de::DeserializeSeed;
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::{AdaptedComponent, MappedSerializer};
+
+#[allow(unused)]
+use crate::BevyObject;
+
+/// A [`MappedSerializer`] that serializes through [`AppTypeRegistry`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReflectSerde;
+
+/// Serialize a component on the active entity through [`AppTypeRegistry`].
+pub type ReflectComponent<T> = AdaptedComponent<T, ReflectSerde>;
+
+impl<T: Reflect + FromReflect + TypePath> MappedSerializer<T> for ReflectSerde {
+    fn serialize<S: Serializer>(item: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        SerUtils::with_world::<S, _>(|world| {
+            let Some(registry) = world.get_resource::<AppTypeRegistry>() else {
+                return Err(serde::ser::Error::custom("AppTypeRegistry missing."));
+            };
+            let registry = registry.read();
+            TypedReflectSerializer::new(item.as_partial_reflect(), &registry).serialize(serializer)
+        })?
+    }
+
+    fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        let Some(registry) = DeUtils::with_world_mut::<D, _>(|world| {
+            world.get_resource::<AppTypeRegistry>().cloned()
+        })?
+        else {
+            return Err(serde::de::Error::custom("AppTypeRegistry missing."));
+        };
+        let registry = registry.read();
+        let Some(registration) = registry.get(TypeId::of::<T>()) else {
+            return Err(serde::de::Error::custom(format!(
+                "{} is not registered.",
+                T::type_path()
+            )));
+        };
+        let value =
+            TypedReflectDeserializer::new(registration, &registry).deserialize(deserializer)?;
+        T::from_reflect(&*value).ok_or_else(|| {
+            serde::de::Error::custom(format!("Failed to convert {}.", T::type_path()))
+        })
+    }
+}
@@ -0,0 +1,301 @@
+//! Module for replicating worlds over the network.
+//!
+//! The server saves a [`BatchSerialization`] type as a [`Snapshot`] every tick,
+//! each root object is prefixed with the id of its entity on the server.
+//! The client keeps a [`ReplicaMap`] from server entities to local entities,
+//! so objects seen before update their existing entities instead of being spawned again.
+//!
+//! ```
+//! // Server
+//! let snapshot = server.snapshot::<SaveData>(tick, Postcard)?;
+//! send(snapshot.to_bytes());
+//!
+//! // Client
+//! let snapshot = Snapshot::from_bytes(&packet)?;
+//! client.apply_snapshot::<SaveData>(&snapshot, Postcard)?;
+//! ```
+//!
+//! A compact binary format like [`Postcard`](crate::format) is recommended.
+//!
+//! # Note
+//!
+//! * Each snapshot contains all replicated objects, mapped entities
+//!   not present in a snapshot are despawned.
+//! * Snapshots older than the last applied snapshot are ignored.
+//! * Children of replicated objects are despawned and deserialized again every snapshot.
+//! * Components missing from an object are not removed from its existing entity.
+use bevy::ecs::entity::Entity;
+use bevy::ecs::hierarchy::Children;
+use bevy::ecs::resource::Resource;
+use bevy::ecs::world::World;
+use bevy_serde_lens_core::{DeUtils, ScopeUtils};
+use rustc_hash::{FxHashMap, FxHashSet};
+use scoped_tls_hkt::scoped_thread_local;
+use serde::de::{SeqAccess, Visitor};
+use serde::ser::SerializeSeq;
+use serde::{Deserialize, Deserializer, Serializer};
+use std::marker::PhantomData;
+
+use crate::error::LoadError;
+use crate::format::SaveFormat;
+use crate::root::RootObject;
+use crate::save_file::SaveFileError;
+use crate::{BatchSerialization, BevyObject, WorldExtension, transaction};
+
+scoped_thread_local!(
+    static SER_REPLICA: ()
+);
+
+scoped_thread_local!(
+    static mut DE_REPLICA: ReplicaScope
+);
+
+/// A serialized [`BatchSerialization`] type at a server tick.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    pub tick: u64,
+    pub data: Vec<u8>,
+}
+
+impl Snapshot {
+    /// Encode as the tick in little endian followed by data.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.data.len() + 8);
+        bytes.extend_from_slice(&self.tick.to_le_bytes());
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+
+    /// Decode bytes created by [`Snapshot::to_bytes`], returns `None` if truncated.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (tick, data) = bytes.split_first_chunk::<8>()?;
+        Some(Snapshot {
+            tick: u64::from_le_bytes(*tick),
+            data: data.to_vec(),
+        })
+    }
+}
+
+/// A [`Resource`] that maps server entities to local entities on a client.
+#[derive(Debug, Default, Resource)]
+pub struct ReplicaMap {
+    entities: FxHashMap<u64, Entity>,
+    tick: Option<u64>,
+}
+
+impl ReplicaMap {
+    /// Returns the local entity of a server entity.
+    pub fn get(&self, remote: Entity) -> Option<Entity> {
+        self.entities.get(&remote.to_bits()).copied()
+    }
+
+    /// Tick of the last applied snapshot.
+    pub fn tick(&self) -> Option<u64> {
+        self.tick
+    }
+
+    /// Number of mapped entities.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Iterate over [`Entity::to_bits`] of server entities and their local entities.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, Entity)> + '_ {
+        self.entities
+            .iter()
+            .map(|(remote, local)| (*remote, *local))
+    }
+}
+
+/// State of an `apply_snapshot` call.
+struct ReplicaScope {
+    entities: FxHashMap<u64, Entity>,
+    /// Server entities present in the snapshot.
+    seen: FxHashSet<u64>,
+    /// Previous values of changed entries, restored if `load` fails.
+    changed: Vec<(u64, Option<Entity>)>,
+    /// Existing entity to deserialize the next root object into.
+    target: Option<Entity>,
+}
+
+/// Returns the existing entity the current root object should be deserialized into.
+///
+/// Old children are despawned once `load` succeeds.
+pub(crate) fn take_target(world: &mut World) -> Option<Entity> {
+    if !DE_REPLICA.is_set() {
+        return None;
+    }
+    let entity = DE_REPLICA.with(|scope| scope.target.take())?;
+    let children = world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+    transaction::on_commit(world, move |world| {
+        for child in children {
+            let _ = world.despawn(child);
+        }
+    });
+    Some(entity)
+}
+
+/// Returns true if roots should be serialized with [`serialize_root`].
+pub(crate) fn is_snapshot() -> bool {
+    SER_REPLICA.is_set()
+}
+
+/// Serialize roots of a [`BevyObject`] with the ids of their entities.
+pub(crate) fn serialize_root<T: BevyObject, S: Serializer>(
+    world: &mut World,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if T::IS_QUERY {
+        let mut query = world.query_filtered::<(Entity, T::Data), T::Filter>();
+        return ScopeUtils::serialize_scope(world, || {
+            let items = query.iter(world).collect::<Vec<_>>();
+            let mut seq = serializer.serialize_seq(Some(items.len()))?;
+            for (entity, data) in items {
+                seq.serialize_element(&(entity.to_bits(), T::into_ser(data)))?;
+            }
+            seq.end()
+        });
+    }
+    let mut query = world.query_filtered::<Entity, T::Filter>();
+    let entities = query.iter(world).collect::<Vec<_>>();
+    let mut seq = serializer.serialize_seq(Some(entities.len()))?;
+    for entity in entities {
+        ScopeUtils::serialize_scope(world, || {
+            ScopeUtils::current_entity_scope(entity, || {
+                seq.serialize_element(&(entity.to_bits(), T::init()))
+            })
+        })?;
+    }
+    seq.end()
+}
+
+/// Returns true if roots should be deserialized as [`Replicated`].
+pub(crate) fn is_replicating() -> bool {
+    DE_REPLICA.is_set()
+}
+
+/// A root object prefixed with the id of its entity on the server.
+pub(crate) struct Replicated<T>(pub RootObject<T>);
+
+impl<'de, T: BevyObject> Deserialize<'de> for Replicated<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(2, ReplicatedVisitor(PhantomData))
+    }
+}
+
+struct ReplicatedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: BevyObject> Visitor<'de> for ReplicatedVisitor<T> {
+    type Value = Replicated<T>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a server entity and an object")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let Some(remote) = seq.next_element::<u64>()? else {
+            return Err(serde::de::Error::invalid_length(0, &self));
+        };
+        let local = DE_REPLICA.with(|scope| scope.entities.get(&remote).copied());
+        let target = DeUtils::with_world_mut_err::<A::Error, _>(|world| {
+            local.filter(|entity| world.get_entity(*entity).is_ok())
+        })?;
+        DE_REPLICA.with(|scope| scope.target = target);
+        let object = seq.next_element::<RootObject<T>>();
+        DE_REPLICA.with(|scope| scope.target = None);
+        let Some(object) = object? else {
+            return Err(serde::de::Error::invalid_length(1, &self));
+        };
+        DE_REPLICA.with(|scope| {
+            scope.seen.insert(remote);
+            if local != Some(object.get()) {
+                let prev = scope.entities.insert(remote, object.get());
+                scope.changed.push((remote, prev));
+            }
+        });
+        Ok(Replicated(object))
+    }
+}
+
+/// Extension methods on [`World`] for replication.
+pub trait ReplicationExtension {
+    /// Save a [`BatchSerialization`] type as a [`Snapshot`],
+    /// root objects are prefixed with their entities.
+    fn snapshot<T: BatchSerialization>(
+        &mut self,
+        tick: u64,
+        format: impl SaveFormat,
+    ) -> Result<Snapshot, SaveFileError>;
+
+    /// Apply a [`Snapshot`] created by `snapshot`, updating entities
+    /// mapped in [`ReplicaMap`] and despawning mapped entities not in the snapshot.
+    ///
+    /// Returns `false` if the snapshot is older than the last applied snapshot.
+    fn apply_snapshot<T: BatchSerialization>(
+        &mut self,
+        snapshot: &Snapshot,
+        format: impl SaveFormat,
+    ) -> Result<bool, LoadError>;
+}
+
+impl ReplicationExtension for World {
+    fn snapshot<T: BatchSerialization>(
+        &mut self,
+        tick: u64,
+        format: impl SaveFormat,
+    ) -> Result<Snapshot, SaveFileError> {
+        let data = SER_REPLICA.set(&(), || self.save_to_bytes::<T>(format))?;
+        Ok(Snapshot { tick, data })
+    }
+
+    fn apply_snapshot<T: BatchSerialization>(
+        &mut self,
+        snapshot: &Snapshot,
+        format: impl SaveFormat,
+    ) -> Result<bool, LoadError> {
+        let mut replica = self.remove_resource::<ReplicaMap>().unwrap_or_default();
+        if replica.tick.is_some_and(|tick| snapshot.tick <= tick) {
+            self.insert_resource(replica);
+            return Ok(false);
+        }
+        let mut scope = ReplicaScope {
+            entities: std::mem::take(&mut replica.entities),
+            seen: FxHashSet::default(),
+            changed: Vec::new(),
+            target: None,
+        };
+        let result = DE_REPLICA.set(&mut scope, || {
+            self.load_from_bytes::<T>(&snapshot.data, format)
+        });
+        if result.is_err() {
+            for (remote, prev) in scope.changed.into_iter().rev() {
+                match prev {
+                    Some(local) => scope.entities.insert(remote, local),
+                    None => scope.entities.remove(&remote),
+                };
+            }
+            replica.entities = scope.entities;
+            self.insert_resource(replica);
+            return result.map(|_| false);
+        }
+        scope.entities.retain(|remote, local| {
+            if scope.seen.contains(remote) {
+                return true;
+            }
+            let _ = self.despawn(*local);
+            false
+        });
+        self.flush();
+        replica.entities = scope.entities;
+        replica.tick = Some(snapshot.tick);
+        self.insert_resource(replica);
+        Ok(true)
+    }
+}
@@ -7,7 +7,10 @@ use serde::{
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
-use crate::{BevyObject, ZstInit};
+use crate::error::{self, PathSegment};
+use crate::lenient::Lenient;
+use crate::replication::{self, Replicated};
+use crate::{BevyObject, ZstInit, merge, transaction};
 
 /// Building block item.
 ///
@@ -23,16 +26,37 @@ impl<T> RootObject<T> {
 
 impl<'de, T: BevyObject> Deserialize<'de> for RootObject<T> {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        let id = DeUtils::with_world_mut::<D, _>(|w| w.spawn_empty().id())?;
-        if let Err(e) =
+        // Replicated objects are deserialized into their existing entities.
+        let target = DeUtils::with_world_mut::<D, _>(replication::take_target)?;
+        let id = match target {
+            Some(entity) => entity,
+            None => {
+                let id = DeUtils::with_world_mut::<D, _>(|w| w.spawn_empty().id())?;
+                transaction::record_spawn(id);
+                id
+            }
+        };
+        let (result, redirect) = merge::root_scope(|| {
             ScopeUtils::current_entity_scope(id, || T::Object::deserialize(deserializer))
-        {
+        });
+        if let Err(e) = result {
+            if target.is_none() {
+                DeUtils::with_world_mut::<D, _>(|w| {
+                    if let Ok(entity) = w.get_entity_mut(id) {
+                        entity.despawn();
+                    }
+                })?;
+            }
+            return Err(e);
+        }
+        // Merged into an existing entity, `id` is empty.
+        if let Some(existing) = redirect {
             DeUtils::with_world_mut::<D, _>(|w| {
                 if let Ok(entity) = w.get_entity_mut(id) {
                     entity.despawn();
                 }
             })?;
-            return Err(e);
+            return Ok(RootObject(existing, PhantomData));
         }
         Ok(RootObject(id, PhantomData))
     }
@@ -73,7 +97,31 @@ impl<'de, T: BevyObject> Visitor<'de> for Root<T> {
     where
         A: SeqAccess<'de>,
     {
-        while let Some(item) = seq.next_element::<RootObject<T>>()? {
+        if replication::is_replicating() {
+            let mut index = 0;
+            while let Some(Replicated(item)) = error::path_scope(
+                || PathSegment::Index(index),
+                || seq.next_element::<Replicated<T>>(),
+            )? {
+                index += 1;
+                DeUtils::with_world_mut_err::<A::Error, _>(|world| {
+                    if let Some(mut root) = T::get_root(world) {
+                        root.add_child(item.get());
+                    }
+                })?
+            }
+            return Ok(Root(PhantomData));
+        }
+        let mut index = 0;
+        while let Some(Lenient(item)) = error::path_scope(
+            || PathSegment::Index(index),
+            || seq.next_element::<Lenient<RootObject<T>>>(),
+        )? {
+            index += 1;
+            // Skipped in lenient mode.
+            let Some(item) = item else {
+                continue;
+            };
             DeUtils::with_world_mut_err::<A::Error, _>(|world| {
                 if let Some(mut root) = T::get_root(world) {
                     root.add_child(item.get());
@@ -0,0 +1,328 @@
+//! Module for loading and saving with `bevy_asset`.
+//!
+//! [`SaveFile<T>`] is an [`Asset`] containing a parsed save of `T` as a [`Value`].
+//! Parsing happens in [`SaveFileLoader`] off the main thread,
+//! while applying the save to the world happens in a system.
+//!
+//! ```
+//! app.add_plugins(SaveFilePlugin::<SaveData, Json>::default());
+//!
+//! fn load_game(mut commands: Commands, asset_server: Res<AssetServer>) {
+//!     commands.spawn(LoadSaveFile::<SaveData>::new(asset_server.load("saves/1.json")));
+//! }
+//!
+//! fn on_loaded(mut reader: MessageReader<SaveFileLoaded<SaveData>>) {
+//!     for loaded in reader.read() {
+//!         if let Err(err) = &loaded.result {
+//!             error!("{err}");
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! # Note
+//!
+//! Save files require a self describing format like `json` or `ron`.
+//! See the [`format`](crate::format) module for available formats.
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use bevy::app::{App, Plugin, PreUpdate};
+use bevy::asset::io::{Reader, Writer};
+use bevy::asset::saver::{AssetSaver, SavedAsset};
+use bevy::asset::{
+    Asset, AssetApp, AssetLoader, AssetPath, AssetServer, Assets, Handle, LoadContext, LoadState,
+};
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::Entity;
+use bevy::ecs::message::Message;
+use bevy::ecs::world::World;
+use bevy::reflect::TypePath;
+use bevy::reflect::utility::GenericTypePathCell;
+use bevy::tasks::futures_lite::AsyncWriteExt;
+use bevy::utils::prelude::ShortName;
+
+use crate::error::LoadError;
+use crate::format::{FormatError, SaveFormat};
+use crate::value::{self, Value, ValueSerializer};
+use crate::{BatchSerialization, WorldExtension};
+
+/// Error of [`SaveFileLoader`] and [`SaveFileSaver`].
+#[derive(Debug, thiserror::Error)]
+pub enum SaveFileError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Format(FormatError),
+}
+
+/// Implement [`TypePath`] by type name, since [`BatchSerialization`] types
+/// usually do not implement [`TypePath`].
+macro_rules! impl_type_path {
+    ([$($impl_g:tt)*] $name: ident [$($ty_g: tt)*]) => {
+        impl<$($impl_g)*> TypePath for $name<$($ty_g)*> {
+            fn type_path() -> &'static str {
+                std::any::type_name::<Self>()
+            }
+
+            fn short_type_path() -> &'static str {
+                static CELL: GenericTypePathCell = GenericTypePathCell::new();
+                CELL.get_or_insert::<Self, _>(|| ShortName::of::<Self>().to_string())
+            }
+
+            fn type_ident() -> Option<&'static str> {
+                Some(stringify!($name))
+            }
+
+            fn crate_name() -> Option<&'static str> {
+                Some("bevy_serde_lens")
+            }
+
+            fn module_path() -> Option<&'static str> {
+                Some(module_path!())
+            }
+        }
+    };
+}
+
+/// An [`Asset`] containing a parsed save file of `T`.
+#[derive(Asset)]
+pub struct SaveFile<T: BatchSerialization + 'static> {
+    pub value: Value,
+    p: PhantomData<fn() -> T>,
+}
+
+impl_type_path!([T: BatchSerialization + 'static] SaveFile [T]);
+
+impl<T: BatchSerialization + 'static> Debug for SaveFile<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SaveFile")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<T: BatchSerialization + 'static> Clone for SaveFile<T> {
+    fn clone(&self) -> Self {
+        Self::from_value(self.value.clone())
+    }
+}
+
+impl<T: BatchSerialization + 'static> SaveFile<T> {
+    /// Create a [`SaveFile`] from a parsed save of `T`.
+    pub fn from_value(value: Value) -> Self {
+        SaveFile {
+            value,
+            p: PhantomData,
+        }
+    }
+
+    /// Save `T` in the world as a [`SaveFile`].
+    pub fn new(world: &mut World) -> Result<Self, value::Error> {
+        Ok(Self::from_value(world.save::<T, _>(ValueSerializer)?))
+    }
+
+    /// Load `T` from this [`SaveFile`].
+    pub fn apply(&self, world: &mut World) -> Result<(), LoadError> {
+        world.try_load::<T, _>(self.value.clone())
+    }
+
+    /// Write this [`SaveFile`] as bytes in a [`SaveFormat`].
+    pub fn to_bytes(&self, format: &dyn SaveFormat) -> Result<Vec<u8>, SaveFileError> {
+        format.write(&self.value).map_err(SaveFileError::Format)
+    }
+
+    /// Parse a [`SaveFile`] from bytes in a self describing [`SaveFormat`].
+    pub fn from_bytes(bytes: &[u8], format: &dyn SaveFormat) -> Result<Self, SaveFileError> {
+        format
+            .parse(bytes)
+            .map(Self::from_value)
+            .map_err(SaveFileError::Format)
+    }
+}
+
+/// [`AssetLoader`] of [`SaveFile<T>`] in a [`SaveFormat`].
+pub struct SaveFileLoader<T, F> {
+    pub format: F,
+    p: PhantomData<fn() -> T>,
+}
+
+impl_type_path!([T: 'static, F: 'static] SaveFileLoader [T, F]);
+
+impl<T, F> SaveFileLoader<T, F> {
+    pub fn new(format: F) -> Self {
+        SaveFileLoader {
+            format,
+            p: PhantomData,
+        }
+    }
+}
+
+impl<T, F: Debug> Debug for SaveFileLoader<T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SaveFileLoader")
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+impl<T: BatchSerialization + 'static, F: SaveFormat> AssetLoader for SaveFileLoader<T, F> {
+    type Asset = SaveFile<T>;
+    type Settings = ();
+    type Error = SaveFileError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        SaveFile::from_bytes(&bytes, &self.format)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.format.extensions()
+    }
+}
+
+/// [`AssetSaver`] of [`SaveFile<T>`] in a [`SaveFormat`].
+pub struct SaveFileSaver<T, F> {
+    pub format: F,
+    p: PhantomData<fn() -> T>,
+}
+
+impl_type_path!([T: 'static, F: 'static] SaveFileSaver [T, F]);
+
+impl<T, F> SaveFileSaver<T, F> {
+    pub fn new(format: F) -> Self {
+        SaveFileSaver {
+            format,
+            p: PhantomData,
+        }
+    }
+}
+
+impl<T, F: Debug> Debug for SaveFileSaver<T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SaveFileSaver")
+            .field("format", &self.format)
+            .finish()
+    }
+}
+
+impl<T: BatchSerialization + 'static, F: SaveFormat> AssetSaver for SaveFileSaver<T, F> {
+    type Asset = SaveFile<T>;
+    type Settings = ();
+    type OutputLoader = SaveFileLoader<T, F>;
+    type Error = SaveFileError;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, '_, Self::Asset>,
+        _: &Self::Settings,
+        _: AssetPath<'_>,
+    ) -> Result<(), Self::Error> {
+        let bytes = asset.to_bytes(&self.format)?;
+        writer.write_all(&bytes).await?;
+        Ok(())
+    }
+}
+
+/// Load a [`SaveFile`] into the world as `T` once it finishes loading.
+///
+/// The entity is despawned after the save is applied and [`SaveFileLoaded`] is written.
+#[derive(Debug, Component)]
+pub struct LoadSaveFile<T: BatchSerialization + 'static> {
+    pub handle: Handle<SaveFile<T>>,
+    p: PhantomData<fn() -> T>,
+}
+
+impl<T: BatchSerialization + 'static> LoadSaveFile<T> {
+    pub fn new(handle: Handle<SaveFile<T>>) -> Self {
+        LoadSaveFile {
+            handle,
+            p: PhantomData,
+        }
+    }
+}
+
+/// Written when a [`LoadSaveFile`] request is completed or failed.
+#[derive(Debug, Message)]
+pub struct SaveFileLoaded<T: BatchSerialization + 'static> {
+    pub handle: Handle<SaveFile<T>>,
+    pub result: Result<(), LoadError>,
+}
+
+/// Apply loaded [`LoadSaveFile`] requests to the world.
+///
+/// The [`SaveFile`] stays in [`Assets`] until all of its handles are dropped,
+/// so the same save can be applied again.
+pub fn apply_save_files<T: BatchSerialization + 'static>(world: &mut World) {
+    let mut query = world.query::<(Entity, &LoadSaveFile<T>)>();
+    let requests = query
+        .iter(world)
+        .map(|(entity, request)| (entity, request.handle.clone()))
+        .collect::<Vec<_>>();
+    for (entity, handle) in requests {
+        let save = world
+            .get_resource::<Assets<SaveFile<T>>>()
+            .and_then(|assets| assets.get(&handle))
+            .map(|save| save.value.clone());
+        let result = match save {
+            Some(value) => world.try_load::<T, _>(value),
+            None => match world
+                .get_resource::<AssetServer>()
+                .map(|server| server.get_load_state(&handle))
+            {
+                Some(Some(LoadState::NotLoaded | LoadState::Loading)) => continue,
+                Some(Some(LoadState::Failed(err))) => Err(serde::de::Error::custom(err)),
+                Some(Some(LoadState::Loaded)) => Err(serde::de::Error::custom(
+                    "SaveFile is loaded but missing from Assets.",
+                )),
+                Some(None) => Err(serde::de::Error::custom(
+                    "SaveFile is not tracked by the AssetServer.",
+                )),
+                None => Err(serde::de::Error::custom("AssetServer is missing.")),
+            },
+        };
+        let _ = world.despawn(entity);
+        world.write_message(SaveFileLoaded::<T> { handle, result });
+    }
+}
+
+/// Register [`SaveFile<T>`] with a [`SaveFormat`] and apply [`LoadSaveFile<T>`] requests in [`PreUpdate`].
+pub struct SaveFilePlugin<T, F> {
+    format: F,
+    p: PhantomData<fn() -> T>,
+}
+
+impl<T, F> SaveFilePlugin<T, F> {
+    pub fn new(format: F) -> Self {
+        SaveFilePlugin {
+            format,
+            p: PhantomData,
+        }
+    }
+}
+
+impl<T, F: Default> Default for SaveFilePlugin<T, F> {
+    fn default() -> Self {
+        Self::new(F::default())
+    }
+}
+
+impl<T, F> Plugin for SaveFilePlugin<T, F>
+where
+    T: BatchSerialization + 'static,
+    F: SaveFormat + Clone,
+{
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SaveFile<T>>()
+            .register_asset_loader(SaveFileLoader::<T, F>::new(self.format.clone()))
+            .add_message::<SaveFileLoaded<T>>()
+            .add_systems(PreUpdate, apply_save_files::<T>);
+    }
+}
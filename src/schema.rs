@@ -0,0 +1,751 @@
+//! Module for exporting the layout of [`BatchSerialization`] types as JSON Schema.
+//!
+//! ```
+//! let schema = world.schema::<SaveFile>();
+//! std::fs::write("save.schema.json", serde_json::to_string_pretty(&schema)?)?;
+//! ```
+//!
+//! The layout of [`BevyObject`]s is known statically, components
+//! and resources are traced through their [`Deserialize`] implementations,
+//! registered [`TypeTagged`](crate::typetagged::TypeTagged) names
+//! are read from the world.
+//!
+//! # Limitations
+//!
+//! * Types that use `deserialize_any`, like `#[serde(untagged)]` or `#[serde(flatten)]`,
+//!   and types that cannot be constructed from placeholder values become [`Schema::Any`].
+//! * Whether a field is optional cannot be traced, so no fields are marked as required.
+//! * Enums are exported as definitions by their serde name, which should be unique.
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use rustc_hash::FxHashMap;
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::ser::SerializeMap;
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::{BatchSerialization, ZstInit};
+
+#[allow(unused)]
+use crate::BevyObject;
+#[allow(unused)]
+use serde::Deserialize;
+
+/// Maximum nesting of sequences, maps and options to trace.
+const MAX_DEPTH: usize = 32;
+/// Maximum number of passes to trace enum variants.
+const MAX_PASSES: usize = 256;
+
+thread_local! {
+    static TRACING: Cell<bool> = const { Cell::new(false) };
+    static PROVIDED: RefCell<Option<Schema>> = const { RefCell::new(None) };
+    static DEFINITIONS: RefCell<BTreeMap<String, Schema>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// Layout of a serialized value, serializes as JSON Schema.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Schema {
+    /// Any value.
+    #[default]
+    Any,
+    Null,
+    Bool,
+    Integer,
+    Number,
+    String,
+    Bytes,
+    /// Homogeneous sequence.
+    Array(Box<Schema>),
+    /// Fixed length sequence.
+    Tuple(Vec<Schema>),
+    /// Map with arbitrary keys.
+    Map(Box<Schema>),
+    /// Struct with named fields.
+    Object(Vec<(String, Schema)>),
+    /// Value or null.
+    Nullable(Box<Schema>),
+    /// Externally tagged enum.
+    Variants {
+        unit: Vec<String>,
+        tagged: Vec<(String, Schema)>,
+    },
+    /// Reference to a definition by name.
+    Ref(String),
+}
+
+impl Schema {
+    /// Serialize the fields of this schema into a map.
+    fn serialize_entries<M: SerializeMap>(&self, map: &mut M) -> Result<(), M::Error> {
+        match self {
+            Schema::Any => Ok(()),
+            Schema::Null => map.serialize_entry("type", "null"),
+            Schema::Bool => map.serialize_entry("type", "boolean"),
+            Schema::Integer => map.serialize_entry("type", "integer"),
+            Schema::Number => map.serialize_entry("type", "number"),
+            Schema::String => map.serialize_entry("type", "string"),
+            Schema::Bytes => {
+                map.serialize_entry("type", "array")?;
+                map.serialize_entry("items", &Schema::Integer)
+            }
+            Schema::Array(item) => {
+                map.serialize_entry("type", "array")?;
+                map.serialize_entry("items", item)
+            }
+            Schema::Tuple(items) => {
+                map.serialize_entry("type", "array")?;
+                map.serialize_entry("prefixItems", items)?;
+                map.serialize_entry("minItems", &items.len())?;
+                map.serialize_entry("maxItems", &items.len())
+            }
+            Schema::Map(value) => {
+                map.serialize_entry("type", "object")?;
+                map.serialize_entry("additionalProperties", value)
+            }
+            Schema::Object(fields) => {
+                map.serialize_entry("type", "object")?;
+                map.serialize_entry("properties", &Properties(fields))
+            }
+            Schema::Nullable(item) => map.serialize_entry("anyOf", &[item.as_ref(), &Schema::Null]),
+            Schema::Variants { unit, tagged } => {
+                map.serialize_entry("oneOf", &OneOf { unit, tagged })
+            }
+            Schema::Ref(name) => map.serialize_entry("$ref", &format!("#/$defs/{name}")),
+        }
+    }
+}
+
+impl Serialize for Schema {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        self.serialize_entries(&mut map)?;
+        map.end()
+    }
+}
+
+struct Properties<'t>(&'t [(String, Schema)]);
+
+impl Serialize for Properties<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+struct OneOf<'t> {
+    unit: &'t [String],
+    tagged: &'t [(String, Schema)],
+}
+
+struct UnitVariants<'t>(&'t [String]);
+
+impl Serialize for UnitVariants<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry("enum", self.0)?;
+        map.end()
+    }
+}
+
+struct TaggedVariant<'t>(&'t str, &'t Schema);
+
+impl Serialize for TaggedVariant<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(4))?;
+        map.serialize_entry("type", "object")?;
+        map.serialize_entry(
+            "properties",
+            &Properties(&[(self.0.to_owned(), self.1.clone())]),
+        )?;
+        map.serialize_entry("required", &[self.0])?;
+        map.serialize_entry("additionalProperties", &false)?;
+        map.end()
+    }
+}
+
+impl Serialize for OneOf<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(None)?;
+        if !self.unit.is_empty() {
+            seq.serialize_element(&UnitVariants(self.unit))?;
+        }
+        for (name, schema) in self.tagged {
+            seq.serialize_element(&TaggedVariant(name, schema))?;
+        }
+        seq.end()
+    }
+}
+
+/// A JSON Schema document with definitions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RootSchema {
+    pub schema: Schema,
+    pub definitions: BTreeMap<String, Schema>,
+}
+
+impl Serialize for RootSchema {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("$schema", "https://json-schema.org/draft/2020-12/schema")?;
+        self.schema.serialize_entries(&mut map)?;
+        if !self.definitions.is_empty() {
+            map.serialize_entry("$defs", &self.definitions)?;
+        }
+        map.end()
+    }
+}
+
+/// Generate the [`RootSchema`] of a [`BatchSerialization`] type.
+///
+/// Prefer `World::schema` which makes registered type tags available.
+pub fn root_schema<T: BatchSerialization>() -> RootSchema {
+    let prev = DEFINITIONS.with(|d| std::mem::take(&mut *d.borrow_mut()));
+    let schema = T::schema();
+    let definitions = DEFINITIONS.with(|d| std::mem::replace(&mut *d.borrow_mut(), prev));
+    RootSchema {
+        schema,
+        definitions,
+    }
+}
+
+/// Add a named definition if not already defined and return a reference to it.
+///
+/// This allows recursive layouts.
+pub fn define(name: &str, f: impl FnOnce() -> Schema) -> Schema {
+    let exists = DEFINITIONS.with(|d| {
+        let mut d = d.borrow_mut();
+        if d.contains_key(name) {
+            true
+        } else {
+            d.insert(name.to_owned(), Schema::Any);
+            false
+        }
+    });
+    if !exists {
+        let schema = f();
+        // Tracing an enum with the same name already filled in its variants.
+        DEFINITIONS.with(|d| {
+            if let Some(slot) = d.borrow_mut().get_mut(name).filter(|x| **x == Schema::Any) {
+                *slot = schema;
+            }
+        });
+    }
+    Schema::Ref(name.to_owned())
+}
+
+/// Returns true if currently tracing a schema.
+pub(crate) fn is_tracing() -> bool {
+    TRACING.get()
+}
+
+/// When tracing, provide the schema of a marker type instead of deserializing it.
+pub(crate) fn provided<T: ZstInit>(f: impl FnOnce() -> Schema) -> Option<T> {
+    if !is_tracing() {
+        return None;
+    }
+    provide(f());
+    Some(T::init())
+}
+
+/// Override the schema of the value currently being traced.
+pub(crate) fn provide(schema: Schema) {
+    PROVIDED.with(|p| *p.borrow_mut() = Some(schema));
+}
+
+fn take_provided(slot: &mut Schema) {
+    if let Some(schema) = PROVIDED.with(|p| p.borrow_mut().take()) {
+        *slot = schema;
+    }
+}
+
+/// Trace the schema of a type through its [`Deserialize`] implementation.
+pub fn trace<T: DeserializeOwned>() -> Schema {
+    trace_with(|tracer| T::deserialize(tracer).map(|_| ()))
+}
+
+/// Trace the schema of a deserialization function.
+pub(crate) fn trace_with(mut f: impl FnMut(Tracer) -> Result<(), TraceError>) -> Schema {
+    let prev = TRACING.replace(true);
+    let mut state = TraceState::default();
+    let mut result = Schema::Any;
+    for pass in 0..MAX_PASSES {
+        let mut slot = Schema::Any;
+        let ok = f(Tracer {
+            slot: &mut slot,
+            state: &mut state,
+            depth: 0,
+        })
+        .is_ok();
+        take_provided(&mut slot);
+        if pass == 0 && ok {
+            result = slot;
+        }
+        if !state.advance() {
+            break;
+        }
+    }
+    state.finish();
+    TRACING.set(prev);
+    result
+}
+
+/// Error used in schema tracing.
+#[derive(Debug)]
+pub struct TraceError(String);
+
+impl Display for TraceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TraceError {}
+
+impl serde::de::Error for TraceError {
+    fn custom<T: Display>(msg: T) -> Self {
+        TraceError(msg.to_string())
+    }
+}
+
+enum VariantSchema {
+    Unit,
+    Content(Schema),
+}
+
+struct EnumTrace {
+    variants: &'static [&'static str],
+    schemas: Vec<Option<VariantSchema>>,
+    choice: usize,
+}
+
+#[derive(Default)]
+struct TraceState {
+    enums: FxHashMap<&'static str, EnumTrace>,
+    /// Enum and variant chosen for the current pass.
+    attempt: Option<(&'static str, usize)>,
+}
+
+impl TraceState {
+    /// Choose the next variant to trace, returns false if all variants are traced.
+    fn advance(&mut self) -> bool {
+        // Unreachable or failed variants are not retried.
+        if let Some((name, index)) = self.attempt.take() {
+            if let Some(trace) = self.enums.get_mut(name) {
+                trace.schemas[index].get_or_insert(VariantSchema::Content(Schema::Any));
+            }
+        }
+        let mut names: Vec<_> = self.enums.keys().copied().collect();
+        names.sort_unstable();
+        for name in names {
+            let trace = self.enums.get_mut(name).unwrap();
+            if let Some(index) = trace.schemas.iter().position(Option::is_none) {
+                trace.choice = index;
+                self.attempt = Some((name, index));
+                return true;
+            }
+        }
+        false
+    }
+
+    fn finish(self) {
+        for (name, trace) in self.enums {
+            let mut unit = Vec::new();
+            let mut tagged = Vec::new();
+            for (variant, schema) in trace.variants.iter().zip(trace.schemas) {
+                match schema {
+                    Some(VariantSchema::Unit) => unit.push((*variant).to_owned()),
+                    Some(VariantSchema::Content(schema)) => {
+                        tagged.push(((*variant).to_owned(), schema))
+                    }
+                    None => tagged.push(((*variant).to_owned(), Schema::Any)),
+                }
+            }
+            DEFINITIONS.with(|d| {
+                d.borrow_mut()
+                    .insert(name.to_owned(), Schema::Variants { unit, tagged })
+            });
+        }
+    }
+}
+
+/// A [`Deserializer`] that records the layout requested by a [`Deserialize`] implementation.
+pub(crate) struct Tracer<'t> {
+    slot: &'t mut Schema,
+    state: &'t mut TraceState,
+    depth: usize,
+}
+
+fn trace_seed<'de, T: DeserializeSeed<'de>>(
+    seed: T,
+    slot: &mut Schema,
+    state: &mut TraceState,
+    depth: usize,
+) -> Result<T::Value, TraceError> {
+    if depth > MAX_DEPTH {
+        return Err(serde::de::Error::custom("Maximum depth exceeded."));
+    }
+    let value = seed.deserialize(Tracer { slot, state, depth });
+    take_provided(slot);
+    value
+}
+
+macro_rules! trace_primitive {
+    ($($fn: ident, $visit: ident, $schema: ident, $value: expr;)*) => {
+        $(
+            fn $fn<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                *self.slot = Schema::$schema;
+                visitor.$visit($value)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Tracer<'_> {
+    type Error = TraceError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(serde::de::Error::custom(
+            "`deserialize_any` cannot be traced.",
+        ))
+    }
+
+    trace_primitive! {
+        deserialize_bool, visit_bool, Bool, false;
+        deserialize_i8, visit_i8, Integer, 1;
+        deserialize_i16, visit_i16, Integer, 1;
+        deserialize_i32, visit_i32, Integer, 1;
+        deserialize_i64, visit_i64, Integer, 1;
+        deserialize_i128, visit_i128, Integer, 1;
+        deserialize_u8, visit_u8, Integer, 1;
+        deserialize_u16, visit_u16, Integer, 1;
+        deserialize_u32, visit_u32, Integer, 1;
+        deserialize_u64, visit_u64, Integer, 1;
+        deserialize_u128, visit_u128, Integer, 1;
+        deserialize_f32, visit_f32, Number, 1.0;
+        deserialize_f64, visit_f64, Number, 1.0;
+        deserialize_char, visit_char, String, 'a';
+        deserialize_str, visit_str, String, "";
+        deserialize_string, visit_str, String, "";
+        deserialize_bytes, visit_bytes, Bytes, &[];
+        deserialize_byte_buf, visit_bytes, Bytes, &[];
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let mut inner = Schema::Any;
+        let result = if self.depth >= MAX_DEPTH {
+            visitor.visit_none()
+        } else {
+            let result = visitor.visit_some(Tracer {
+                slot: &mut inner,
+                state: self.state,
+                depth: self.depth + 1,
+            });
+            take_provided(&mut inner);
+            result
+        };
+        *self.slot = Schema::Nullable(Box::new(inner));
+        result
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        *self.slot = Schema::Null;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.slot = Schema::Null;
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let slot = self.slot;
+        let result = visitor.visit_newtype_struct(Tracer {
+            slot: &mut *slot,
+            state: self.state,
+            depth: self.depth,
+        });
+        take_provided(slot);
+        result
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let len = if self.depth >= MAX_DEPTH { 0 } else { 1 };
+        let mut seq = TraceSeq {
+            items: vec![Schema::Any],
+            len,
+            index: 0,
+            state: self.state,
+            depth: self.depth + 1,
+        };
+        let result = visitor.visit_seq(&mut seq);
+        *self.slot = Schema::Array(Box::new(seq.items.pop().unwrap_or_default()));
+        result
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let mut seq = TraceSeq {
+            items: vec![Schema::Any; len],
+            len,
+            index: 0,
+            state: self.state,
+            depth: self.depth + 1,
+        };
+        let result = visitor.visit_seq(&mut seq);
+        *self.slot = Schema::Tuple(seq.items);
+        result
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let mut map = TraceMap {
+            value: Schema::Any,
+            remaining: usize::from(self.depth < MAX_DEPTH),
+            state: self.state,
+            depth: self.depth + 1,
+        };
+        let result = visitor.visit_map(&mut map);
+        *self.slot = Schema::Map(Box::new(map.value));
+        result
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let mut map = TraceStruct {
+            fields: fields
+                .iter()
+                .map(|f| ((*f).to_owned(), Schema::Any))
+                .collect(),
+            keys: fields,
+            index: 0,
+            state: self.state,
+            depth: self.depth + 1,
+        };
+        let result = visitor.visit_map(&mut map);
+        *self.slot = Schema::Object(map.fields);
+        result
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        if variants.is_empty() {
+            return Err(serde::de::Error::custom("Empty enum."));
+        }
+        let trace = self.state.enums.entry(name).or_insert_with(|| EnumTrace {
+            variants,
+            schemas: variants.iter().map(|_| None).collect(),
+            choice: 0,
+        });
+        let index = trace.choice;
+        *self.slot = Schema::Ref(name.to_owned());
+        let mut content = None;
+        let result = visitor.visit_enum(TraceEnum {
+            variant: variants[index],
+            content: &mut content,
+            state: self.state,
+            depth: self.depth + 1,
+        });
+        if let (Some(trace), Some(content)) = (self.state.enums.get_mut(name), content) {
+            trace.schemas[index].get_or_insert(content);
+        }
+        result
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(serde::de::Error::custom(
+            "Free standing identifiers cannot be traced.",
+        ))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct TraceSeq<'t> {
+    items: Vec<Schema>,
+    len: usize,
+    index: usize,
+    state: &'t mut TraceState,
+    depth: usize,
+}
+
+impl<'de> SeqAccess<'de> for TraceSeq<'_> {
+    type Error = TraceError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let slot = &mut self.items[self.index];
+        self.index += 1;
+        trace_seed(seed, slot, self.state, self.depth).map(Some)
+    }
+}
+
+struct TraceMap<'t> {
+    value: Schema,
+    remaining: usize,
+    state: &'t mut TraceState,
+    depth: usize,
+}
+
+impl<'de> MapAccess<'de> for TraceMap<'_> {
+    type Error = TraceError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        trace_seed(seed, &mut Schema::Any, self.state, self.depth).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        trace_seed(seed, &mut self.value, self.state, self.depth)
+    }
+}
+
+struct TraceStruct<'t> {
+    fields: Vec<(String, Schema)>,
+    keys: &'static [&'static str],
+    index: usize,
+    state: &'t mut TraceState,
+    depth: usize,
+}
+
+impl<'de> MapAccess<'de> for TraceStruct<'_> {
+    type Error = TraceError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some(key) = self.keys.get(self.index) else {
+            return Ok(None);
+        };
+        seed.deserialize(IntoDeserializer::<TraceError>::into_deserializer(*key))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let slot = &mut self.fields[self.index].1;
+        self.index += 1;
+        trace_seed(seed, slot, self.state, self.depth)
+    }
+}
+
+struct TraceEnum<'t> {
+    variant: &'static str,
+    content: &'t mut Option<VariantSchema>,
+    state: &'t mut TraceState,
+    depth: usize,
+}
+
+impl<'de, 't> EnumAccess<'de> for TraceEnum<'t> {
+    type Error = TraceError;
+    type Variant = TraceEnum<'t>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let value = seed.deserialize(IntoDeserializer::<TraceError>::into_deserializer(
+            self.variant,
+        ))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for TraceEnum<'_> {
+    type Error = TraceError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        *self.content = Some(VariantSchema::Unit);
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, Self::Error> {
+        let mut schema = Schema::Any;
+        let result = trace_seed(seed, &mut schema, self.state, self.depth);
+        *self.content = Some(VariantSchema::Content(schema));
+        result
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let mut schema = Schema::Any;
+        let result = Tracer {
+            slot: &mut schema,
+            state: self.state,
+            depth: self.depth,
+        }
+        .deserialize_tuple(len, visitor);
+        *self.content = Some(VariantSchema::Content(schema));
+        result
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let mut schema = Schema::Any;
+        let result = Tracer {
+            slot: &mut schema,
+            state: self.state,
+            depth: self.depth,
+        }
+        .deserialize_struct("", fields, visitor);
+        *self.content = Some(VariantSchema::Content(schema));
+        result
+    }
+}
@@ -0,0 +1,232 @@
+use std::any::TypeId;
+
+use bevy::ecs::component::Component;
+use bevy::ecs::entity::{Entity, EntityHashSet};
+use bevy::ecs::resource::Resource;
+use bevy::ecs::world::World;
+use bevy_serde_lens_core::DeUtils;
+use rustc_hash::{FxHashMap, FxHashSet};
+use scoped_tls_hkt::scoped_thread_local;
+use serde::Deserializer;
+
+scoped_thread_local!(
+    pub(crate) static mut JOURNAL: Journal
+);
+
+type WorldFn = Box<dyn FnOnce(&mut World)>;
+
+type SnapshotFn = fn(&World) -> Option<WorldFn>;
+
+/// Resources restored by a failed `load`, see `WorldExtension::register_rollback`.
+#[derive(Resource, Default)]
+pub(crate) struct RollbackRegistry(FxHashMap<TypeId, SnapshotFn>);
+
+impl RollbackRegistry {
+    pub(crate) fn register<T: Resource + Clone>(&mut self) {
+        self.0.insert(TypeId::of::<T>(), |world| {
+            let prev = world.get_resource::<T>()?.clone();
+            Some(Box::new(move |world| world.insert_resource(prev)))
+        });
+    }
+}
+
+/// Changes made to the world during a `load`, undone if the `load` fails.
+#[derive(Default)]
+pub(crate) struct Journal {
+    /// Entities spawned during the `load`.
+    spawned: EntityHashSet,
+    /// Undo functions in the order of changes.
+    undo: Vec<WorldFn>,
+    /// Destructive changes deferred until the `load` succeeds.
+    commit: Vec<WorldFn>,
+    /// Resources recorded by [`record_resource`].
+    resources: FxHashSet<TypeId>,
+}
+
+impl Journal {
+    /// Apply deferred changes.
+    fn commit(self, world: &mut World) {
+        for f in self.commit {
+            f(world)
+        }
+        world.flush();
+    }
+
+    /// Undo all changes in reverse order and despawn spawned entities.
+    fn rollback(self, world: &mut World) {
+        for f in self.undo.into_iter().rev() {
+            f(world)
+        }
+        for entity in self.spawned {
+            if let Ok(entity) = world.get_entity_mut(entity) {
+                entity.despawn();
+            }
+        }
+        world.flush();
+    }
+}
+
+/// Run `f` in a journal, changes are committed if `f` succeeds and rolled back otherwise.
+///
+/// Nested scopes join the outermost scope.
+pub(crate) fn scope<T, E>(
+    world: &mut World,
+    f: impl FnOnce(&mut World) -> Result<T, E>,
+) -> Result<T, E> {
+    if JOURNAL.is_set() {
+        return f(world);
+    }
+    let mut journal = Journal::default();
+    let result = JOURNAL.set(&mut journal, || f(world));
+    match result {
+        Ok(_) => journal.commit(world),
+        Err(_) => journal.rollback(world),
+    }
+    result
+}
+
+/// Position in the journal, changes after which can be undone by [`rollback_to`].
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Checkpoint {
+    undo: usize,
+    commit: usize,
+}
+
+/// Returns the current position in the journal.
+pub(crate) fn checkpoint() -> Checkpoint {
+    if !JOURNAL.is_set() {
+        return Checkpoint::default();
+    }
+    JOURNAL.with(|journal| Checkpoint {
+        undo: journal.undo.len(),
+        commit: journal.commit.len(),
+    })
+}
+
+/// Undo changes made after a [`Checkpoint`] and discard deferred changes.
+///
+/// Spawned entities are not despawned since `RootObject` despawns itself on failure.
+pub(crate) fn rollback_to(world: &mut World, checkpoint: Checkpoint) {
+    if !JOURNAL.is_set() {
+        return;
+    }
+    let undo = JOURNAL.with(|journal| {
+        journal.commit.truncate(checkpoint.commit);
+        // Snapshots taken after the checkpoint are undone, take them again on the next change.
+        journal.resources.clear();
+        journal
+            .undo
+            .split_off(checkpoint.undo.min(journal.undo.len()))
+    });
+    for f in undo.into_iter().rev() {
+        f(world)
+    }
+}
+
+/// Record an entity spawned during `load`.
+pub(crate) fn record_spawn(entity: Entity) {
+    if JOURNAL.is_set() {
+        JOURNAL.with(|journal| journal.spawned.insert(entity));
+    }
+}
+
+/// Record a function that undoes a change.
+pub(crate) fn record(f: impl FnOnce(&mut World) + 'static) {
+    if JOURNAL.is_set() {
+        JOURNAL.with(|journal| journal.undo.push(Box::new(f)));
+    }
+}
+
+/// Run a destructive change once `load` succeeds, or immediately if not in a `load` scope.
+pub(crate) fn on_commit(world: &mut World, f: impl FnOnce(&mut World) + 'static) {
+    if JOURNAL.is_set() {
+        JOURNAL.with(|journal| journal.commit.push(Box::new(f)));
+    } else {
+        f(world)
+    }
+}
+
+/// Record the previous value of a component on the current entity before it is replaced.
+///
+/// Entities spawned during `load` are not recorded since they are despawned anyway.
+pub(crate) fn record_insert<'de, T: Component, D: Deserializer<'de>>() -> Result<(), D::Error> {
+    if !JOURNAL.is_set() {
+        return Ok(());
+    }
+    let entity = DeUtils::current_entity::<D>()?;
+    DeUtils::with_world_mut::<D, _>(|world| record_replace::<T>(world, entity))
+}
+
+/// Record the previous value of a component on an entity before it is replaced.
+///
+/// Entities spawned during `load` are not recorded since they are despawned anyway.
+pub(crate) fn record_replace<T: Component>(world: &mut World, entity: Entity) {
+    if !JOURNAL.is_set() || JOURNAL.with(|journal| journal.spawned.contains(&entity)) {
+        return;
+    }
+    let prev = world
+        .get_entity_mut(entity)
+        .ok()
+        .and_then(|mut entity| entity.take::<T>());
+    record(move |world| {
+        let Ok(mut entity) = world.get_entity_mut(entity) else {
+            return;
+        };
+        match prev {
+            Some(prev) => {
+                entity.insert(prev);
+            }
+            None => {
+                entity.remove::<T>();
+            }
+        }
+    });
+}
+
+/// Record a copy of a resource before its first change in a `load`,
+/// if registered in [`RollbackRegistry`].
+pub(crate) fn record_resource<T: Resource>(world: &mut World) {
+    if !JOURNAL.is_set() {
+        return;
+    }
+    JOURNAL.with(|journal| {
+        if !journal.resources.insert(TypeId::of::<T>()) {
+            return;
+        }
+        let undo = world
+            .get_resource::<RollbackRegistry>()
+            .and_then(|registry| registry.0.get(&TypeId::of::<T>()))
+            .and_then(|snapshot| snapshot(world));
+        if let Some(undo) = undo {
+            journal.undo.push(undo);
+        }
+    });
+}
+
+/// Insert a resource, recording the previous value.
+pub(crate) fn replace_resource<T: Resource>(world: &mut World, value: T) {
+    if JOURNAL.is_set() {
+        let prev = world.remove_resource::<T>();
+        record(move |world| match prev {
+            Some(prev) => world.insert_resource(prev),
+            None => {
+                world.remove_resource::<T>();
+            }
+        });
+    }
+    world.insert_resource(value);
+}
+
+/// Insert a non-send resource, recording the previous value.
+pub(crate) fn replace_non_send<T: 'static>(world: &mut World, value: T) {
+    if JOURNAL.is_set() {
+        let prev = world.remove_non_send::<T>();
+        record(move |world| match prev {
+            Some(prev) => world.insert_non_send(prev),
+            None => {
+                world.remove_non_send::<T>();
+            }
+        });
+    }
+    world.insert_non_send(value);
+}
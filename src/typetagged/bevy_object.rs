@@ -1,7 +1,8 @@
+use crate::schema::{self, Schema};
 use crate::{BevyObject, SerializeComponent, ZstInit};
 use bevy::ecs::{component::Component, query::With};
 use bevy::reflect::TypePath;
-use bevy_serde_lens_core::{DeUtils, SerUtils};
+use bevy_serde_lens_core::SerUtils;
 use ref_cast::RefCast;
 use serde::Deserialize;
 use serde::Serialize;
@@ -31,6 +32,10 @@ impl<T: Component + TypePath + ErasedObject> BevyObject for $ty<T> {
         T::short_type_path()
     }
 
+    fn schema() -> Schema {
+        $ty::<T>::schema()
+    }
+
     fn into_ser(query_data: $crate::Item<'_, Self>) -> impl Serialize {
         $ty::ref_cast(query_data)
     }
@@ -51,8 +56,10 @@ impl<T: Component + ErasedObject> serde::Serialize for $ty<SerializeComponent<T>
 impl<'de, T: Component + ErasedObject> Deserialize<'de> for $ty<SerializeComponent<T>> {
     fn deserialize<D: serde::Deserializer<'de>,>(deserializer: D) -> Result<Self, D::Error>
     {
-        let component = $ty::<T>::deserialize(deserializer)?;
-        DeUtils::insert::<D>(component)?;
+        if let Some(this) = schema::provided(|| $ty::<T>::schema()) {
+            return Ok(this);
+        }
+        crate::entity::insert_component::<T, D>(|| $ty::<T>::deserialize(deserializer))?;
         Ok(ZstInit::init())
     }
 }
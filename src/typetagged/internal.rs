@@ -10,12 +10,17 @@ use erased_serde::Deserializer;
 use rustc_hash::FxHashMap;
 use serde::de::{DeserializeOwned, DeserializeSeed, Visitor};
 
+use crate::schema::{self, Schema};
 use crate::typetagged::ErasedObject;
 
 scoped_tls_hkt::scoped_thread_local! {
     pub(crate) static TYPETAG_SERVER: TypeTagServer
 }
 
+thread_local! {
+    static TAGGED_SCHEMAS: std::cell::RefCell<Vec<TypeId>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
 type DeserializeFn<T> = fn(&mut dyn erased_serde::Deserializer) -> Result<T, erased_serde::Error>;
 
 /// A [`Resource`] that stores registered deserialization functions.
@@ -33,6 +38,19 @@ impl TypeTagServer {
             .copied()
     }
 
+    /// Returns registered names of an [`ErasedObject`] in alphabetical order.
+    pub fn names<T: ErasedObject>(&self) -> Vec<&str> {
+        let id = TypeId::of::<T>();
+        let mut names: Vec<_> = self
+            .functions
+            .keys()
+            .filter(|(ty, _)| *ty == id)
+            .map(|(_, name)| name.as_ref())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
     pub fn clear(&mut self) {
         self.functions.clear();
     }
@@ -152,3 +170,63 @@ impl<'de, T: ErasedObject> DeserializeSeed<'de> for DeserializeFnSeed<'de, T> {
         (self.0)(&mut <dyn Deserializer>::erase(deserializer)).map_err(serde::de::Error::custom)
     }
 }
+
+/// Schema of an [`ErasedObject`] as an externally tagged enum of registered names.
+pub(crate) fn tagged_schema<V: ErasedObject>() -> Schema {
+    if !TYPETAG_SERVER.is_set() {
+        return Schema::Any;
+    }
+    let id = TypeId::of::<V>();
+    // Recursive type tags are not expanded.
+    if TAGGED_SCHEMAS.with(|s| s.borrow().contains(&id)) {
+        return Schema::Any;
+    }
+    TAGGED_SCHEMAS.with(|s| s.borrow_mut().push(id));
+    let functions: Vec<_> = TYPETAG_SERVER.with(|server| {
+        server
+            .names::<V>()
+            .into_iter()
+            .filter_map(|name| Some((name.to_owned(), server.get::<V>(name)?)))
+            .collect()
+    });
+    let tagged = functions
+        .into_iter()
+        .map(|(name, de_fn)| {
+            let schema = schema::trace_with(|tracer| {
+                de_fn(&mut <dyn Deserializer>::erase(tracer))
+                    .map(|_| ())
+                    .map_err(serde::de::Error::custom)
+            });
+            (name, schema)
+        })
+        .collect();
+    TAGGED_SCHEMAS.with(|s| s.borrow_mut().pop());
+    Schema::Variants {
+        unit: Vec::new(),
+        tagged,
+    }
+}
+
+/// Deserialize an [`ErasedObject`] from a schema tracer using the first registered name.
+pub(crate) fn trace_tagged<'de, V: ErasedObject, D: serde::Deserializer<'de>>(
+    deserializer: D,
+    schema: Schema,
+) -> Result<V, D::Error> {
+    let de_fn = TYPETAG_SERVER
+        .is_set()
+        .then(|| {
+            TYPETAG_SERVER.with(|server| {
+                let name = *server.names::<V>().first()?;
+                server.get::<V>(name)
+            })
+        })
+        .flatten();
+    let Some(de_fn) = de_fn else {
+        return Err(serde::de::Error::custom(
+            "cannot trace `TypeTagged` value without registered type-tags.",
+        ));
+    };
+    let value = DeserializeFnSeed(de_fn, PhantomData).deserialize(deserializer);
+    schema::provide(schema);
+    value
+}
@@ -51,6 +51,7 @@ use std::{
 };
 
 use crate::impl_with_notation_newtype;
+use crate::schema::{self, Schema};
 
 /// A serializable trait object of an [`ErasedObject`].
 ///
@@ -330,11 +331,35 @@ where
     }
 }
 
+impl<V: ErasedObject> TypeTagged<V> {
+    /// Schema of registered type-tags.
+    pub(crate) fn schema() -> Schema {
+        internal::tagged_schema::<V>()
+    }
+}
+
+impl<V: ErasedObject> AnyOrTagged<V> {
+    /// Untagged values cannot be traced.
+    pub(crate) fn schema() -> Schema {
+        Schema::Any
+    }
+}
+
+impl<V: ErasedObject> SmartTagged<V> {
+    /// Untagged values cannot be traced.
+    pub(crate) fn schema() -> Schema {
+        Schema::Any
+    }
+}
+
 impl<'de, V: ErasedObject> serde::Deserialize<'de> for TypeTagged<V> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
+        if schema::is_tracing() {
+            return internal::trace_tagged(deserializer, Self::schema()).map(TypeTagged);
+        }
         deserializer
             .deserialize_map(TypeTaggedVisitor::<V>(PhantomData))
             .map(TypeTagged)
@@ -346,6 +371,9 @@ impl<'de, V: ErasedObject> serde::Deserialize<'de> for AnyOrTagged<V> {
     where
         D: serde::Deserializer<'de>,
     {
+        if schema::is_tracing() {
+            return internal::trace_tagged(deserializer, Self::schema()).map(AnyOrTagged);
+        }
         deserializer
             .deserialize_any(TypeTaggedVisitor::<V>(PhantomData))
             .map(AnyOrTagged)
@@ -357,6 +385,9 @@ impl<'de, V: ErasedObject> serde::Deserialize<'de> for SmartTagged<V> {
     where
         D: serde::Deserializer<'de>,
     {
+        if schema::is_tracing() {
+            return internal::trace_tagged(deserializer, Self::schema()).map(SmartTagged);
+        }
         if deserializer.is_human_readable() {
             deserializer
                 .deserialize_any(TypeTaggedVisitor::<V>(PhantomData))
@@ -0,0 +1,137 @@
+use bevy::ecs::{component::Component, world::EntityRef, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::chunk::ChunkExtension;
+use bevy_serde_lens::format::{DeserializeFn, FormatError, SaveFormat};
+use bevy_serde_lens::{BevyObject, ChildVec, WorldExtension, batch};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, TypePath)]
+pub struct Json;
+
+impl SaveFormat for Json {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        f(&mut <dyn erased_serde::Deserializer>::erase(
+            &mut deserializer,
+        ))?;
+        deserializer.end()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Position {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Tree(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Potion(String);
+
+#[derive(BevyObject)]
+pub struct Item {
+    potion: Potion,
+}
+
+#[derive(BevyObject)]
+pub struct Unit {
+    position: Position,
+    hp: Hp,
+    #[serde(default)]
+    items: ChildVec<Item>,
+}
+
+#[derive(BevyObject)]
+pub struct Forest {
+    position: Position,
+    tree: Tree,
+}
+
+type SaveFile = batch!(Unit, Forest);
+
+fn chunk_of(entity: &EntityRef) -> Option<(i32, i32)> {
+    let position = entity.get::<Position>()?;
+    Some((position.x.div_euclid(16), position.y.div_euclid(16)))
+}
+
+fn positions(world: &mut World) -> Vec<(i32, i32)> {
+    let mut query = world.query::<&Position>();
+    let mut result = query.iter(world).map(|p| (p.x, p.y)).collect::<Vec<_>>();
+    result.sort();
+    result
+}
+
+fn setup() -> World {
+    let mut world = World::new();
+    world
+        .spawn((Position { x: 1, y: 1 }, Hp(10)))
+        .with_children(|spawner| {
+            spawner.spawn(Potion("hp".to_owned()));
+        });
+    world.spawn((Position { x: 20, y: 1 }, Hp(5)));
+    world.spawn((Position { x: -3, y: 2 }, Tree(4)));
+    world.spawn((Position { x: 5, y: 5 }, Tree(2)));
+    world
+}
+
+#[test]
+pub fn test() {
+    let mut world = setup();
+    let chunks = world.save_chunks::<SaveFile, _>(chunk_of, Json).unwrap();
+    assert_eq!(chunks.len(), 3);
+
+    let origin = world
+        .save_chunk::<SaveFile, _>(&(0, 0), chunk_of, Json)
+        .unwrap();
+    assert_eq!(origin, chunks[&(0, 0)]);
+
+    // Unload a chunk, children are despawned with their roots.
+    world.despawn_chunk::<SaveFile, _>(&(0, 0), chunk_of);
+    assert_eq!(positions(&mut world), vec![(-3, 2), (20, 1)]);
+    assert_eq!(world.query::<&Potion>().iter(&world).count(), 0);
+
+    // Load only spawns objects in the chunk.
+    world.load_chunk::<SaveFile>(&origin, Json).unwrap();
+    assert_eq!(
+        positions(&mut world),
+        vec![(-3, 2), (1, 1), (5, 5), (20, 1)]
+    );
+    assert_eq!(world.query::<&Potion>().iter(&world).count(), 1);
+
+    // Nothing is spawned if the chunk has trailing bytes.
+    let mut trailing = chunks[&(1, 0)].clone();
+    trailing.extend_from_slice(b" []");
+    assert!(world.load_chunk::<SaveFile>(&trailing, Json).is_err());
+    assert_eq!(
+        positions(&mut world),
+        vec![(-3, 2), (1, 1), (5, 5), (20, 1)]
+    );
+
+    // Load every chunk into a new world.
+    let mut other = World::new();
+    for bytes in chunks.values() {
+        other.load_chunk::<SaveFile>(bytes, Json).unwrap();
+    }
+    assert_eq!(positions(&mut other), positions(&mut setup()));
+
+    // `despawn_bound_objects` is not affected.
+    world.despawn_bound_objects::<SaveFile>();
+    assert_eq!(world.entity_count(), 0);
+}
@@ -0,0 +1,120 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use bevy::ecs::observer::On;
+use bevy::ecs::resource::Resource;
+use bevy::ecs::system::ResMut;
+use bevy::ecs::{component::Component, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::format::{DeserializeFn, FormatError, SaveFormat};
+use bevy_serde_lens::{
+    BevyObject, CommandsExtension, LoadCompleted, RequestId, SaveCompleted, SaveTarget,
+    SerializeResource, WorldExtension, batch,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, TypePath)]
+pub struct Json;
+
+impl SaveFormat for Json {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        f(&mut <dyn erased_serde::Deserializer>::erase(
+            &mut deserializer,
+        ))?;
+        deserializer.end()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Name(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Resource, TypePath)]
+#[serde(transparent)]
+pub struct Turn(u32);
+
+#[derive(BevyObject)]
+pub struct Unit {
+    name: Name,
+}
+
+type SaveData = batch!(Unit, SerializeResource<Turn>);
+
+#[derive(Clone, Default)]
+pub struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Resource)]
+pub struct Results {
+    saved: Vec<(RequestId, Option<PathBuf>, bool)>,
+    loaded: Vec<(RequestId, bool)>,
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    world.init_resource::<Results>();
+    world.add_observer(|event: On<SaveCompleted>, mut results: ResMut<Results>| {
+        results
+            .saved
+            .push((event.id, event.path.clone(), event.result.is_ok()));
+    });
+    world.add_observer(|event: On<LoadCompleted>, mut results: ResMut<Results>| {
+        results.loaded.push((event.id, event.result.is_ok()));
+    });
+    world.spawn(Name("Alice".to_owned()));
+    world.insert_resource(Turn(4));
+
+    let buffer = Buffer::default();
+    let missing = PathBuf::from("missing_dir/save.json");
+    let a = world
+        .commands()
+        .save::<SaveData, _>(SaveTarget::writer(buffer.clone()), Json);
+    let b = world.commands().save::<SaveData, _>(missing.clone(), Json);
+    assert_ne!(a, b);
+    world.flush();
+    assert_eq!(
+        world.resource::<Results>().saved,
+        vec![(a, None, true), (b, Some(missing), false)]
+    );
+
+    let bytes = buffer.0.lock().unwrap().clone();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+        serde_json::json!({"Unit": [{"name": "Alice"}], "Turn": 4})
+    );
+
+    world.despawn_bound_objects::<SaveData>();
+    let a = world.commands().load::<SaveData, _>(bytes, Json);
+    let b = world
+        .commands()
+        .load::<SaveData, _>(b"{\"Turn\": \"invalid\"}", Json);
+    world.flush();
+    assert_eq!(
+        world.resource::<Results>().loaded,
+        vec![(a, true), (b, false)]
+    );
+    assert_eq!(world.resource::<Turn>(), &Turn(4));
+    let mut query = world.query::<&Name>();
+    assert_eq!(query.single(&world).unwrap(), &Name("Alice".to_owned()));
+}
@@ -0,0 +1,6 @@
+#[test]
+pub fn test() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/query_*.rs");
+    cases.pass("tests/ui/pass_*.rs");
+}
@@ -0,0 +1,217 @@
+use bevy::ecs::{component::Component, entity::Entity, hierarchy::Children, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::{BevyObject, ChildVec, Maybe, WorldExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Id(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Potion(String);
+
+#[derive(Debug, Component)]
+pub struct Selected(bool);
+
+#[derive(BevyObject)]
+pub struct Unit {
+    id: Id,
+    hp: Hp,
+    #[serde(default)]
+    potions: ChildVec<Potion>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Mana(u32);
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct Stats {
+    id: Id,
+    hp: Hp,
+}
+
+#[derive(BevyObject)]
+pub struct Caster {
+    id: Id,
+    hp: Maybe<Hp>,
+    #[bevy_object(no_filter)]
+    mana: Mana,
+}
+
+fn find(world: &mut World, id: u32) -> Option<Entity> {
+    let mut query = world.query::<(Entity, &Id)>();
+    query
+        .iter(world)
+        .find(|(_, x)| x.0 == id)
+        .map(|(entity, _)| entity)
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    let a = world.spawn((Id(1), Hp(10))).id();
+    let b = world
+        .spawn((Id(2), Hp(20)))
+        .with_children(|b| {
+            b.spawn(Potion("Hp Potion".to_owned()));
+        })
+        .id();
+    let c = world.spawn((Id(3), Hp(30))).id();
+
+    let snapshot = world
+        .save::<Unit, _>(serde_json::value::Serializer)
+        .unwrap();
+    let baseline = world.delta_baseline::<Unit, Id>();
+
+    let delta = world
+        .save_delta::<Unit, Id, _>(&baseline, serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        delta,
+        json!({"spawned": [], "changed": [], "despawned": []})
+    );
+
+    world.entity_mut(a).get_mut::<Hp>().unwrap().0 = 5;
+    world.despawn(c);
+    world.spawn((Id(4), Hp(40)));
+
+    let delta = world
+        .save_delta::<Unit, Id, _>(&baseline, serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        delta,
+        json!({
+            "spawned": [{"id": 4, "hp": 40, "potions": []}],
+            "changed": [{"id": 1, "hp": 5, "potions": []}],
+            "despawned": [3],
+        })
+    );
+
+    let mut loaded = World::new();
+    loaded.load::<Unit, _>(&snapshot).unwrap();
+    loaded.load_delta::<Unit, Id, _>(&delta).unwrap();
+
+    let a = find(&mut loaded, 1).unwrap();
+    let b2 = find(&mut loaded, 2).unwrap();
+    let d = find(&mut loaded, 4).unwrap();
+    assert!(find(&mut loaded, 3).is_none());
+    assert_eq!(loaded.entity(a).get::<Hp>(), Some(&Hp(5)));
+    assert_eq!(loaded.entity(b2).get::<Hp>(), Some(&Hp(20)));
+    assert_eq!(loaded.entity(d).get::<Hp>(), Some(&Hp(40)));
+    assert_eq!(loaded.entity(b2).get::<Children>().unwrap().len(), 1);
+
+    // Changes to children are detected.
+    let baseline = world.delta_baseline::<Unit, Id>();
+    let potion = world.entity(b).get::<Children>().unwrap()[0];
+    world.entity_mut(potion).get_mut::<Potion>().unwrap().0 = "Mp Potion".to_owned();
+    let delta = world
+        .save_delta::<Unit, Id, _>(&baseline, serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        delta,
+        json!({
+            "spawned": [],
+            "changed": [{"id": 2, "hp": 20, "potions": ["Mp Potion"]}],
+            "despawned": [],
+        })
+    );
+
+    loaded.load_delta::<Unit, Id, _>(&delta).unwrap();
+    assert_eq!(find(&mut loaded, 2), Some(b2));
+    let children = loaded.entity(b2).get::<Children>().unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(
+        loaded.entity(children[0]).get::<Potion>(),
+        Some(&Potion("Mp Potion".to_owned()))
+    );
+
+    // Failed deltas are rolled back and nothing is despawned.
+    let count = loaded.entity_count();
+    let err = loaded
+        .load_delta::<Unit, Id, _>(json!({
+            "changed": [{"id": 1, "hp": 1}, {"id": 5, "hp": "full"}],
+            "despawned": [2],
+        }))
+        .unwrap_err();
+    assert!(err.to_string().starts_with("changed[1].Hp: "));
+    assert_eq!(loaded.entity_count(), count);
+    assert_eq!(loaded.entity(a).get::<Hp>(), Some(&Hp(5)));
+    assert!(find(&mut loaded, 2).is_some());
+
+    // Components not in the object are ignored in `query` mode.
+    let baseline = world.delta_baseline::<Stats, Id>();
+    world.entity_mut(a).insert(Selected(true));
+    let delta = world
+        .save_delta::<Stats, Id, _>(&baseline, serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        delta,
+        json!({"spawned": [], "changed": [], "despawned": []})
+    );
+}
+
+#[test]
+pub fn test_maybe() {
+    let mut world = World::new();
+    let a = world.spawn((Id(1), Hp(10), Mana(5))).id();
+    let b = world.spawn((Id(2), Mana(8))).id();
+
+    let baseline = world.delta_baseline::<Caster, Id>();
+    world.entity_mut(a).get_mut::<Hp>().unwrap().0 = 5;
+    world.entity_mut(b).get_mut::<Mana>().unwrap().0 = 3;
+    let delta = world
+        .save_delta::<Caster, Id, _>(&baseline, serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        delta,
+        json!({
+            "spawned": [],
+            "changed": [
+                {"id": 1, "hp": 5, "mana": 5},
+                {"id": 2, "hp": null, "mana": 3},
+            ],
+            "despawned": [],
+        })
+    );
+}
+
+#[test]
+pub fn test_postcard() {
+    let mut world = World::new();
+    world.spawn((Id(1), Hp(10)));
+    world.spawn((Id(2), Hp(20)));
+    let mut loaded = World::new();
+    loaded.spawn((Id(1), Hp(10)));
+    loaded.spawn((Id(2), Hp(20)));
+
+    let baseline = world.delta_baseline::<Unit, Id>();
+    let a = find(&mut world, 1).unwrap();
+    world.entity_mut(a).get_mut::<Hp>().unwrap().0 = 5;
+    let b = find(&mut world, 2).unwrap();
+    world.despawn(b);
+    world.spawn((Id(3), Hp(30)));
+
+    let mut serializer = postcard::Serializer {
+        output: postcard::ser_flavors::AllocVec::new(),
+    };
+    world
+        .save_delta::<Unit, Id, _>(&baseline, &mut serializer)
+        .unwrap();
+    let bytes = postcard::ser_flavors::Flavor::finalize(serializer.output).unwrap();
+
+    let mut deserializer = postcard::Deserializer::from_bytes(&bytes);
+    loaded.load_delta::<Unit, Id, _>(&mut deserializer).unwrap();
+    let a = find(&mut loaded, 1).unwrap();
+    let c = find(&mut loaded, 3).unwrap();
+    assert_eq!(loaded.entity(a).get::<Hp>(), Some(&Hp(5)));
+    assert_eq!(loaded.entity(c).get::<Hp>(), Some(&Hp(30)));
+    assert!(find(&mut loaded, 2).is_none());
+}
@@ -0,0 +1,169 @@
+use bevy::ecs::{component::Component, resource::Resource, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::diff::{DiffEntry, Difference, WorldDiff};
+use bevy_serde_lens::error::{ErrorPath, PathSegment};
+use bevy_serde_lens::value::Value;
+use bevy_serde_lens::{BevyObject, ChildVec, SerializeResource, WorldExtension, batch};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Position {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Potion(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Resource, TypePath)]
+#[serde(transparent)]
+pub struct Turn(u32);
+
+#[derive(BevyObject)]
+pub struct Item {
+    potion: Potion,
+}
+
+#[derive(BevyObject)]
+pub struct Unit {
+    hp: Hp,
+    position: Position,
+    #[serde(default)]
+    items: ChildVec<Item>,
+}
+
+type SaveFile = batch!(Unit, SerializeResource<Turn>);
+
+fn spawn(world: &mut World, hp: u32, x: i32, potions: &[&str]) {
+    world
+        .spawn((Hp(hp), Position { x, y: 0 }))
+        .with_children(|spawner| {
+            for potion in potions {
+                spawner.spawn(Potion(potion.to_string()));
+            }
+        });
+}
+
+fn path(segments: impl IntoIterator<Item = PathSegment>) -> ErrorPath {
+    ErrorPath(segments.into_iter().collect())
+}
+
+fn key(name: &str) -> PathSegment {
+    PathSegment::Key(name.to_owned())
+}
+
+#[test]
+pub fn test() {
+    let mut a = World::new();
+    // Units with children share an archetype, so they are saved in spawn order.
+    spawn(&mut a, 10, 0, &["Hp Potion"]);
+    spawn(&mut a, 20, 1, &["Hp Potion"]);
+    a.insert_resource(Turn(1));
+
+    let mut b = World::new();
+    spawn(&mut b, 10, 0, &["Hp Potion"]);
+    spawn(&mut b, 20, 1, &["Hp Potion"]);
+    b.insert_resource(Turn(1));
+
+    assert!(a.diff::<SaveFile>(&mut b).unwrap().is_empty());
+    let save = a
+        .save::<SaveFile, _>(bevy_serde_lens::value::ValueSerializer)
+        .unwrap();
+    assert!(b.diff_save::<SaveFile>(&save).unwrap().is_empty());
+
+    let mut b = World::new();
+    spawn(&mut b, 10, 2, &["Mp Potion", "Hp Potion"]);
+    spawn(&mut b, 15, 1, &["Hp Potion"]);
+    spawn(&mut b, 40, 3, &["Hp Potion"]);
+    b.insert_resource(Turn(2));
+
+    let report = a.diff::<SaveFile>(&mut b).unwrap();
+    let position = |x: i64| {
+        Value::Map(vec![
+            (Value::String("x".to_owned()), Value::I64(x)),
+            (Value::String("y".to_owned()), Value::I64(0)),
+        ])
+    };
+    let hp_potion = Value::String("Hp Potion".to_owned());
+    let mp_potion = Value::String("Mp Potion".to_owned());
+    let item =
+        |potion: &Value| Value::Map(vec![(Value::String("potion".to_owned()), potion.clone())]);
+    let expected = WorldDiff(vec![
+        DiffEntry {
+            path: path([key("Unit"), PathSegment::Index(0), key("position")]),
+            difference: Difference::Changed {
+                old: position(0),
+                new: position(2),
+            },
+        },
+        DiffEntry {
+            path: path([
+                key("Unit"),
+                PathSegment::Index(0),
+                key("items"),
+                PathSegment::Index(0),
+                key("potion"),
+            ]),
+            difference: Difference::Changed {
+                old: hp_potion.clone(),
+                new: mp_potion,
+            },
+        },
+        DiffEntry {
+            path: path([
+                key("Unit"),
+                PathSegment::Index(0),
+                key("items"),
+                PathSegment::Index(1),
+            ]),
+            difference: Difference::Added(item(&hp_potion)),
+        },
+        DiffEntry {
+            path: path([key("Unit"), PathSegment::Index(1), key("hp")]),
+            difference: Difference::Changed {
+                old: Value::U64(20),
+                new: Value::U64(15),
+            },
+        },
+        DiffEntry {
+            path: path([key("Unit"), PathSegment::Index(2)]),
+            difference: Difference::Added(
+                b.save::<Unit, _>(bevy_serde_lens::value::ValueSerializer)
+                    .unwrap()
+                    .as_seq()
+                    .unwrap()[2]
+                    .clone(),
+            ),
+        },
+        DiffEntry {
+            path: path([key("Turn")]),
+            difference: Difference::Changed {
+                old: Value::U64(1),
+                new: Value::U64(2),
+            },
+        },
+    ]);
+    assert_eq!(report, expected);
+    assert_eq!(report.0[3].to_string(), "~ Unit[1].hp: 20 -> 15");
+    assert_eq!(
+        report.0[4].to_string(),
+        "+ Unit[2]: {\"hp\": 40, \"position\": {\"x\": 3, \"y\": 0}, \"items\": [{\"potion\": \"Hp Potion\"}]}"
+    );
+
+    // Removed objects.
+    let mut c = World::new();
+    spawn(&mut c, 10, 0, &["Hp Potion"]);
+    c.insert_resource(Turn(1));
+    let report = a.diff::<SaveFile>(&mut c).unwrap();
+    assert_eq!(report.0.len(), 1);
+    assert_eq!(
+        report.0[0].to_string(),
+        "- Unit[1]: {\"hp\": 20, \"position\": {\"x\": 1, \"y\": 0}, \"items\": [{\"potion\": \"Hp Potion\"}]}"
+    );
+    assert!(matches!(report.0[0].difference, Difference::Removed(_)));
+}
@@ -0,0 +1,127 @@
+use bevy::ecs::hierarchy::Children;
+use bevy::ecs::{component::Component, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::dynamic::{DynamicBevyObject, DynamicLayout};
+use bevy_serde_lens::{BevyObject, WorldExtension, batch};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Name(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Potion(String);
+
+#[derive(BevyObject)]
+pub struct Character {
+    name: Name,
+}
+
+type SaveFile = batch!(Character, DynamicBevyObject);
+
+fn setup(world: &mut World) {
+    world.register_dynamic_component::<Hp>();
+    world.register_dynamic_component::<Potion>();
+    // Layouts can be loaded from data.
+    let layout: DynamicLayout =
+        serde_json::from_value(json!({"components": ["Hp"], "children": ["Item"]})).unwrap();
+    world.register_dynamic_object("Unit", layout);
+    world.register_dynamic_object("Item", DynamicLayout::new(["Potion"]));
+}
+
+fn save(world: &mut World) -> serde_json::Value {
+    let mut vec = Vec::new();
+    world
+        .save::<SaveFile, _>(&mut serde_json::Serializer::new(&mut vec))
+        .unwrap();
+    serde_json::from_slice(&vec).unwrap()
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    setup(&mut world);
+    world.spawn(Name("Alice".to_owned()));
+    world.spawn(Hp(10)).with_children(|spawner| {
+        spawner.spawn(Potion("hp".to_owned()));
+        spawner.spawn(Potion("mp".to_owned()));
+    });
+    world.spawn(Potion("loot".to_owned()));
+
+    let expected = json!({
+        "Character": [{"name": "Alice"}],
+        "DynamicBevyObject": {
+            "Item": [{"Potion": "loot"}],
+            "Unit": [{"Hp": 10, "Item": [{"Potion": "hp"}, {"Potion": "mp"}]}],
+        }
+    });
+    let value = save(&mut world);
+    assert_eq!(value, expected);
+
+    world.despawn_bound_objects::<SaveFile>();
+    assert_eq!(world.entity_count(), 0);
+
+    world.load::<SaveFile, _>(&value).unwrap();
+    assert_eq!(world.entity_count(), 5);
+    let mut query = world.query::<(&Hp, &Children)>();
+    let (hp, children) = query.single(&world).unwrap();
+    assert_eq!(hp, &Hp(10));
+    assert_eq!(children.len(), 2);
+    assert_eq!(save(&mut world), expected);
+
+    // Errors roll back the load.
+    let mut world = World::new();
+    setup(&mut world);
+    let invalid = json!({
+        "Character": [],
+        "DynamicBevyObject": {
+            "Unit": [{"Hp": 10, "Item": [{"Potion": 1}]}],
+        }
+    });
+    let error = world.try_load::<SaveFile, _>(&invalid).unwrap_err();
+    assert_eq!(
+        error.path.to_string(),
+        "DynamicBevyObject.Unit[0].Item[0].Potion"
+    );
+    assert_eq!(world.entity_count(), 0);
+
+    let missing = json!({"DynamicBevyObject": {"Unit": [{"Item": []}]}});
+    assert!(world.load::<SaveFile, _>(&missing).is_err());
+    let unknown = json!({"DynamicBevyObject": {"Building": []}});
+    assert!(world.load::<SaveFile, _>(&unknown).is_err());
+    assert_eq!(world.entity_count(), 0);
+}
+
+#[test]
+pub fn test_overlapping() {
+    let mut world = World::new();
+    setup(&mut world);
+    world.register_dynamic_object("Healer", DynamicLayout::new(["Hp", "Potion"]));
+    world.spawn((Hp(5), Potion("hp".to_owned())));
+    world.spawn(Potion("loot".to_owned()));
+
+    // Entities are saved once, as the most specific layout.
+    let value = save(&mut world);
+    assert_eq!(
+        value,
+        json!({
+            "Character": [],
+            "DynamicBevyObject": {
+                "Healer": [{"Hp": 5, "Potion": "hp"}],
+                "Item": [{"Potion": "loot"}],
+                "Unit": [],
+            }
+        })
+    );
+
+    world.despawn_bound_objects::<SaveFile>();
+    assert_eq!(world.entity_count(), 0);
+    world.load::<SaveFile, _>(&value).unwrap();
+    assert_eq!(save(&mut world), value);
+}
@@ -0,0 +1,124 @@
+use bevy::ecs::{component::Component, entity::Entity, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::entity::{EntityId, SerializeEntity};
+use bevy_serde_lens::{BevyObject, ChildVec, WorldExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Tag(String);
+
+#[derive(Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Target {
+    #[entities]
+    #[serde(with = "SerializeEntity")]
+    entity: Entity,
+}
+
+#[derive(BevyObject)]
+pub struct Unit {
+    id: EntityId,
+    tag: Tag,
+    target: Target,
+    #[serde(default)]
+    minions: ChildVec<Minion>,
+}
+
+#[derive(BevyObject)]
+pub struct Minion {
+    id: EntityId,
+    tag: Tag,
+}
+
+fn find(world: &mut World, name: &str) -> Entity {
+    let mut query = world.query::<(Entity, &Tag)>();
+    query
+        .iter(world)
+        .find(|(_, tag)| tag.0 == name)
+        .map(|(entity, _)| entity)
+        .unwrap()
+}
+
+fn target(world: &World, entity: Entity) -> Entity {
+    world.entity(entity).get::<Target>().unwrap().entity
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    let a = world.spawn(Tag("A".to_owned())).id();
+    let b = world.spawn(Tag("B".to_owned())).id();
+    let c = world.spawn(Tag("C".to_owned())).id();
+    world.entity_mut(b).add_child(c);
+    world.entity_mut(a).insert(Target { entity: c });
+    world.entity_mut(b).insert(Target { entity: a });
+
+    let value = world
+        .save::<Unit, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        value,
+        json!([
+            {
+                "id": 0,
+                "tag": "A",
+                "target": 1,
+                "minions": [],
+            },
+            {
+                "id": 2,
+                "tag": "B",
+                "target": 0,
+                "minions": [
+                    {
+                        "id": 1,
+                        "tag": "C",
+                    }
+                ],
+            },
+        ])
+    );
+
+    world.despawn_bound_objects::<Unit>();
+    assert_eq!(world.entity_count(), 0);
+
+    // Spawn an entity to offset new entities.
+    world.spawn_empty();
+    world.load::<Unit, _>(&value).unwrap();
+    // Placeholders are despawned.
+    assert_eq!(world.entity_count(), 4);
+
+    let a = find(&mut world, "A");
+    let b = find(&mut world, "B");
+    let c = find(&mut world, "C");
+    assert_eq!(target(&world, a), c);
+    assert_eq!(target(&world, b), a);
+
+    let value2 = world
+        .save::<Unit, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(value, value2);
+
+    world.despawn_bound_objects::<Unit>();
+    assert!(
+        world
+            .load::<Unit, _>(json!([{
+                "id": 0,
+                "tag": "A",
+                "target": 5,
+            }]))
+            .is_err()
+    );
+
+    world.despawn_bound_objects::<Unit>();
+    assert!(
+        world
+            .load::<Unit, _>(json!([
+                { "id": 0, "tag": "A", "target": 0 },
+                { "id": 0, "tag": "B", "target": 0 },
+            ]))
+            .is_err()
+    );
+}
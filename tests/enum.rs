@@ -0,0 +1,95 @@
+use bevy::ecs::{component::Component, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::{BevyObject, Maybe, WorldExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Weapon(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Durability(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Potion(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Stack(u32);
+
+#[derive(BevyObject)]
+pub enum Item {
+    Weapon {
+        weapon: Weapon,
+        durability: Durability,
+    },
+    #[serde(rename = "potion")]
+    Potion {
+        potion: Potion,
+        #[serde(default)]
+        stack: Maybe<Stack>,
+    },
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    world.spawn((Weapon(4), Durability(100)));
+    world.spawn((Potion("hp".into()), Stack(3)));
+    world.spawn(Potion("mp".into()));
+    // Not an `Item`.
+    world.spawn(Weapon(2));
+
+    let value = world
+        .save::<Item, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        value,
+        json!([
+            { "Weapon": { "weapon": 4, "durability": 100 } },
+            { "potion": { "potion": "hp", "stack": 3 } },
+            { "potion": { "potion": "mp", "stack": null } },
+        ])
+    );
+
+    world.despawn_bound_objects::<Item>();
+    assert_eq!(world.entity_count(), 1);
+
+    world.load::<Item, _>(value.clone()).unwrap();
+    assert_eq!(world.entity_count(), 4);
+    assert_eq!(
+        world
+            .save::<Item, _>(serde_json::value::Serializer)
+            .unwrap(),
+        value
+    );
+
+    let mut world = World::new();
+    world
+        .load::<Item, _>(json!([{ "potion": { "potion": "hp" } }]))
+        .unwrap();
+    let mut query = world.query::<(&Potion, Option<&Stack>)>();
+    let (potion, stack) = query.single(&world).unwrap();
+    assert_eq!(potion, &Potion("hp".into()));
+    assert_eq!(stack, None);
+
+    let mut world = World::new();
+    assert!(
+        world
+            .load::<Item, _>(json!([{ "Shield": { "weapon": 4 } }]))
+            .is_err()
+    );
+    assert_eq!(world.entity_count(), 0);
+}
+
+#[test]
+pub fn test_first_match() {
+    let mut world = World::new();
+    world.spawn((Weapon(4), Durability(100), Potion("hp".into())));
+    let value = world
+        .save::<Item, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        value,
+        json!([{ "Weapon": { "weapon": 4, "durability": 100 } }])
+    );
+}
@@ -0,0 +1,190 @@
+use bevy::ecs::{component::Component, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::envelope::{
+    Compression, EnvelopeError, Enveloped, MAGIC, MAX_DECOMPRESSED_LENGTH, open, read_header,
+};
+use bevy_serde_lens::format::{DeserializeFn, FormatError, SaveFormat};
+use bevy_serde_lens::{BevyObject, WorldExtension, batch};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, TypePath)]
+pub struct Json;
+
+impl SaveFormat for Json {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        f(&mut <dyn erased_serde::Deserializer>::erase(
+            &mut deserializer,
+        ))?;
+        deserializer.end()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, TypePath)]
+pub struct OtherJson;
+
+impl SaveFormat for OtherJson {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["other"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Json.serialize(value)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        Json.deserialize(bytes, f)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Name(String);
+
+#[derive(BevyObject)]
+pub struct Unit {
+    name: Name,
+}
+
+type SaveData = batch!(Unit);
+
+fn setup() -> World {
+    let mut world = World::new();
+    world.spawn(Name("Alice".to_owned()));
+    world.spawn(Name("Bob".to_owned()));
+    world
+}
+
+fn round_trip(format: Enveloped<Json>) {
+    let mut world = setup();
+    let bytes = world.save_to_bytes::<SaveData>(format).unwrap();
+    assert!(bytes.starts_with(&MAGIC));
+
+    let (header, payload) = open(&bytes, Some("json")).unwrap();
+    assert_eq!(header.format, "json");
+    assert_eq!(header.crate_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(header.compression, format.compression);
+    assert_eq!(header.decompressed_length, payload.len() as u64);
+    assert!(serde_json::from_slice::<serde_json::Value>(&payload).is_ok());
+
+    let mut world = World::new();
+    world.load_enveloped::<SaveData>(&bytes, Json).unwrap();
+    assert_eq!(world.entity_count(), 2);
+
+    let mut world = World::new();
+    world.load_from_bytes::<SaveData>(&bytes, format).unwrap();
+    assert_eq!(world.entity_count(), 2);
+}
+
+#[test]
+pub fn test() {
+    round_trip(Enveloped::new(Json));
+
+    let mut world = setup();
+    let bytes = world
+        .save_to_bytes::<SaveData>(Enveloped::new(Json))
+        .unwrap();
+
+    let mut world = World::new();
+    let mut corrupted = bytes.clone();
+    *corrupted.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        world.load_enveloped::<SaveData>(&corrupted, Json),
+        Err(EnvelopeError::ChecksumMismatch { .. })
+    ));
+    assert!(
+        world
+            .load_from_bytes::<SaveData>(&corrupted, Enveloped::new(Json))
+            .is_err()
+    );
+    assert_eq!(world.entity_count(), 0);
+
+    assert!(matches!(
+        world.load_enveloped::<SaveData>(&bytes[..bytes.len() - 1], Json),
+        Err(EnvelopeError::LengthMismatch { .. })
+    ));
+    assert!(matches!(
+        world.load_enveloped::<SaveData>(&bytes[..8], Json),
+        Err(EnvelopeError::Truncated)
+    ));
+    assert!(matches!(
+        world.load_enveloped::<SaveData>(b"[]", Json),
+        Err(EnvelopeError::BadMagic)
+    ));
+    assert!(matches!(
+        world.load_enveloped::<SaveData>(&bytes, OtherJson),
+        Err(EnvelopeError::FormatMismatch { .. })
+    ));
+    assert_eq!(world.entity_count(), 0);
+
+    let (header, _) = read_header(&bytes).unwrap();
+    assert_eq!(header.compression, Compression::None);
+
+    let mut unsupported = bytes.clone();
+    unsupported[MAGIC.len()] = 255;
+    assert!(matches!(
+        read_header(&unsupported),
+        Err(EnvelopeError::UnsupportedVersion(255))
+    ));
+
+    // Errors in the payload are still reported as load errors.
+    let bytes = Enveloped::new(Json)
+        .serialize(&serde_json::json!({"Unit": [{"name": 1}]}))
+        .unwrap();
+    assert!(matches!(
+        world.load_enveloped::<SaveData>(&bytes, Json),
+        Err(EnvelopeError::Load(_))
+    ));
+    assert_eq!(world.entity_count(), 0);
+}
+
+/// Overwrite the decompressed length in the header.
+#[cfg(feature = "deflate")]
+fn set_decompressed_length(bytes: &mut [u8], length: u64) {
+    let (header, _) = read_header(bytes).unwrap();
+    // Followed by the payload length, the checksum and the payload.
+    let offset = bytes.len() - header.length as usize - 20;
+    bytes[offset..offset + 8].copy_from_slice(&length.to_le_bytes());
+}
+
+#[cfg(feature = "deflate")]
+#[test]
+pub fn test_deflate() {
+    round_trip(Enveloped::compressed(Json, Compression::Deflate));
+
+    let mut world = setup();
+    let bytes = world
+        .save_to_bytes::<SaveData>(Enveloped::compressed(Json, Compression::Deflate))
+        .unwrap();
+    let (header, _) = read_header(&bytes).unwrap();
+
+    // Decompression stops at the decompressed length.
+    let mut shorter = bytes.clone();
+    set_decompressed_length(&mut shorter, 4);
+    assert!(matches!(
+        open(&shorter, None),
+        Err(EnvelopeError::DecompressedLengthMismatch(4))
+    ));
+    let mut longer = bytes.clone();
+    set_decompressed_length(&mut longer, header.decompressed_length + 1);
+    assert!(matches!(
+        open(&longer, None),
+        Err(EnvelopeError::DecompressedLengthMismatch(_))
+    ));
+
+    let mut too_large = bytes.clone();
+    set_decompressed_length(&mut too_large, MAX_DECOMPRESSED_LENGTH + 1);
+    assert!(matches!(
+        open(&too_large, None),
+        Err(EnvelopeError::TooLarge(_))
+    ));
+}
@@ -0,0 +1,68 @@
+use bevy::ecs::{component::Component, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::error::{ErrorPath, PathSegment};
+use bevy_serde_lens::{BevyObject, ChildVec, WorldExtension, batch};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Potion {
+    amount: u32,
+}
+
+#[derive(BevyObject)]
+pub struct Unit {
+    hp: Hp,
+    #[serde(default)]
+    potions: ChildVec<Potion>,
+}
+
+#[derive(BevyObject)]
+pub struct Building {
+    hp: Hp,
+}
+
+type SaveFile = batch!(Building, Unit);
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    let save = json!({
+        "Building": [{"hp": 100}],
+        "Unit": [
+            {"hp": 10},
+            {"hp": 20, "potions": [{"amount": 1}, {"amount": "full"}]},
+        ],
+    });
+
+    let err = world.try_load::<SaveFile, _>(&save).unwrap_err();
+    assert_eq!(
+        err.path,
+        ErrorPath(vec![
+            PathSegment::Key("Unit".to_owned()),
+            PathSegment::Index(1),
+            PathSegment::Index(1),
+            PathSegment::Component("Potion"),
+        ])
+    );
+    assert_eq!(err.path.to_string(), "Unit[1][1].Potion");
+    assert!(err.to_string().starts_with("Unit[1][1].Potion: "));
+
+    let err = world.load::<SaveFile, _>(&save).unwrap_err();
+    assert!(err.to_string().starts_with("Unit[1][1].Potion: "));
+
+    let err = world
+        .try_load::<SaveFile, _>(json!({"Building": [{"hp": -1}]}))
+        .unwrap_err();
+    assert_eq!(err.path.to_string(), "Building[0].Hp");
+
+    let err = world
+        .try_load::<SaveFile, _>(json!({"Tower": []}))
+        .unwrap_err();
+    assert_eq!(err.path.to_string(), "Tower");
+    assert_eq!(world.entity_count(), 0);
+}
@@ -0,0 +1,106 @@
+use bevy::ecs::{component::Component, entity::Entity, world::EntityRef, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::{BevyObject, ChildVec, WorldExtension, batch};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Region(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Potion(String);
+
+#[derive(BevyObject)]
+pub struct Item {
+    potion: Potion,
+}
+
+#[derive(BevyObject)]
+pub struct Unit {
+    hp: Hp,
+    region: Region,
+    items: ChildVec<Item>,
+}
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct Marker {
+    region: Region,
+}
+
+type SaveFile = batch!(Unit, Marker);
+
+fn save(
+    world: &mut World,
+    f: impl FnOnce(&mut World, &mut serde_json::Serializer<&mut Vec<u8>>),
+) -> serde_json::Value {
+    let mut vec = Vec::new();
+    f(world, &mut serde_json::Serializer::new(&mut vec));
+    serde_json::from_slice(&vec).unwrap()
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    let a = world
+        .spawn((Hp(1), Region(0)))
+        .with_children(|spawner| {
+            spawner.spawn(Potion("hp".to_owned()));
+        })
+        .id();
+    let b = world
+        .spawn((Hp(2), Region(1)))
+        .with_children(|spawner| {
+            spawner.spawn(Potion("mp".to_owned()));
+        })
+        .id();
+    world.spawn(Region(0));
+    world.spawn(Region(1));
+
+    let value = save(&mut world, |world, serializer| {
+        world
+            .save_filtered::<SaveFile, _>(serializer, |entity: &EntityRef| {
+                entity.get::<Region>() == Some(&Region(1))
+            })
+            .unwrap();
+    });
+    assert_eq!(
+        value,
+        json!({
+            "Unit": [{"hp": 2, "region": 1, "items": [{"potion": "mp"}]}],
+            "Marker": [{"region": 1}, {"region": 1}],
+        })
+    );
+
+    let value = save(&mut world, |world, serializer| {
+        world
+            .save_filtered::<Unit, _>(serializer, [a, b].as_slice())
+            .unwrap();
+    });
+    assert_eq!(
+        value,
+        json!([
+            {"hp": 1, "region": 0, "items": [{"potion": "hp"}]},
+            {"hp": 2, "region": 1, "items": [{"potion": "mp"}]},
+        ])
+    );
+
+    let value = save(&mut world, |world, serializer| {
+        world
+            .save_filtered::<SaveFile, _>(serializer, Vec::<Entity>::new())
+            .unwrap();
+    });
+    assert_eq!(value, json!({"Unit": [], "Marker": []}));
+
+    // The filter does not leak into later saves.
+    let value = save(&mut world, |world, serializer| {
+        world.save::<Marker, _>(serializer).unwrap();
+    });
+    assert_eq!(value.as_array().unwrap().len(), 4);
+}
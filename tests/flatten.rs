@@ -0,0 +1,101 @@
+use bevy::ecs::{component::Component, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::{BevyObject, WorldExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Mp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Position(f32, f32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Velocity(f32, f32);
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct Stats(Hp, Mp);
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct Physics {
+    position: Position,
+    velocity: Velocity,
+}
+
+#[derive(BevyObject)]
+pub struct Unit {
+    hp: Hp,
+    #[bevy_object(flatten)]
+    physics: Physics,
+}
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct QueryUnit {
+    mp: Mp,
+    #[bevy_object(flatten)]
+    physics: Physics,
+}
+
+#[test]
+pub fn test_tuple() {
+    let mut world = World::new();
+    world.spawn((Hp(1), Mp(2)));
+    let value = world
+        .save::<Stats, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(value, json!([[1, 2]]));
+
+    world.despawn_bound_objects::<Stats>();
+    world.load::<Stats, _>(value).unwrap();
+    let mut query = world.query::<(&Hp, &Mp)>();
+    assert_eq!(query.single(&world).unwrap(), (&Hp(1), &Mp(2)));
+}
+
+#[test]
+pub fn test_flatten() {
+    let mut world = World::new();
+    world.spawn((Hp(1), Position(1.0, 2.0), Velocity(3.0, 4.0)));
+    let value = world
+        .save::<Unit, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        value,
+        json!([{ "hp": 1, "position": [1.0, 2.0], "velocity": [3.0, 4.0] }])
+    );
+
+    world.despawn_bound_objects::<Unit>();
+    assert_eq!(world.entity_count(), 0);
+    world.load::<Unit, _>(value).unwrap();
+    let mut query = world.query::<(&Hp, &Position, &Velocity)>();
+    assert_eq!(
+        query.single(&world).unwrap(),
+        (&Hp(1), &Position(1.0, 2.0), &Velocity(3.0, 4.0))
+    );
+}
+
+#[test]
+pub fn test_flatten_query() {
+    let mut world = World::new();
+    world.spawn((Mp(1), Position(1.0, 2.0), Velocity(3.0, 4.0)));
+    let value = world
+        .save::<QueryUnit, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        value,
+        json!([{ "mp": 1, "position": [1.0, 2.0], "velocity": [3.0, 4.0] }])
+    );
+
+    world.despawn_bound_objects::<QueryUnit>();
+    world.load::<QueryUnit, _>(value).unwrap();
+    let mut query = world.query::<(&Mp, &Position, &Velocity)>();
+    assert_eq!(
+        query.single(&world).unwrap(),
+        (&Mp(1), &Position(1.0, 2.0), &Velocity(3.0, 4.0))
+    );
+}
@@ -0,0 +1,151 @@
+use bevy::ecs::{component::Component, resource::Resource, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::format::{DeserializeFn, FormatError, SaveFormat, format_from_path};
+use bevy_serde_lens::{BevyObject, SerializeResource, WorldExtension, batch};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default, TypePath)]
+pub struct Json;
+
+impl SaveFormat for Json {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        f(&mut <dyn erased_serde::Deserializer>::erase(
+            &mut deserializer,
+        ))?;
+        deserializer.end()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Name(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Resource, TypePath)]
+#[serde(transparent)]
+pub struct Turn(u32);
+
+#[derive(BevyObject)]
+pub struct Unit {
+    name: Name,
+}
+
+type SaveData = batch!(Unit, SerializeResource<Turn>);
+
+fn setup() -> World {
+    let mut world = World::new();
+    world.spawn(Name("Alice".to_owned()));
+    world.spawn(Name("Bob".to_owned()));
+    world.insert_resource(Turn(4));
+    world
+}
+
+fn names(world: &mut World) -> Vec<String> {
+    let mut query = world.query::<&Name>();
+    let mut names = query.iter(world).map(|x| x.0.clone()).collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+fn round_trip(format: impl SaveFormat + Copy) {
+    let mut world = setup();
+    let bytes = world.save_to_bytes::<SaveData>(format).unwrap();
+    world.despawn_bound_objects::<SaveData>();
+    assert_eq!(world.entity_count(), 0);
+    world.load_from_bytes::<SaveData>(&bytes, format).unwrap();
+    assert_eq!(names(&mut world), vec!["Alice", "Bob"]);
+    assert_eq!(world.resource::<Turn>(), &Turn(4));
+}
+
+#[test]
+pub fn test() {
+    round_trip(Json);
+
+    let mut world = World::new();
+    // Trailing bytes are rejected.
+    assert!(
+        world
+            .load_from_bytes::<SaveData>(b"{\"Turn\": 1} {}", Json)
+            .is_err()
+    );
+    assert!(!world.contains_resource::<Turn>());
+
+    let dir = std::env::temp_dir().join("bevy_serde_lens_format_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("save.json");
+    let mut world = setup();
+    world.save_to_path::<SaveData>(&path, Json).unwrap();
+    assert!(!dir.join("save.json.tmp").exists());
+
+    let bytes = std::fs::read(&path).unwrap();
+    let mut world = World::new();
+    world.load_from_bytes::<SaveData>(&bytes, Json).unwrap();
+    assert_eq!(names(&mut world), vec!["Alice", "Bob"]);
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // Custom formats are not detected.
+    assert!(format_from_path(Path::new("save.unknown")).is_none());
+}
+
+#[cfg(feature = "json")]
+#[test]
+pub fn test_json() {
+    round_trip(bevy_serde_lens::format::Json);
+}
+
+#[cfg(feature = "ron")]
+#[test]
+pub fn test_ron() {
+    round_trip(bevy_serde_lens::format::Ron);
+}
+
+#[cfg(feature = "postcard")]
+#[test]
+pub fn test_postcard() {
+    round_trip(bevy_serde_lens::format::Postcard);
+}
+
+#[cfg(all(feature = "json", feature = "ron"))]
+#[test]
+pub fn test_detect() {
+    let dir = std::env::temp_dir().join("bevy_serde_lens_detect_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, format) in [
+        (
+            "save.json",
+            &bevy_serde_lens::format::Json as &'static dyn SaveFormat,
+        ),
+        ("save.ron", &bevy_serde_lens::format::Ron),
+    ] {
+        let path = dir.join(file);
+        let mut world = setup();
+        world.save_to_path::<SaveData>(&path, format).unwrap();
+        let mut world = World::new();
+        world.load_from_path::<SaveData>(&path).unwrap();
+        assert_eq!(names(&mut world), vec!["Alice", "Bob"]);
+    }
+
+    // Enveloped saves have their own extension and are loaded with the format in the header.
+    let path = dir.join("save.bsle");
+    let mut world = setup();
+    world
+        .save_to_path::<SaveData>(
+            &path,
+            bevy_serde_lens::envelope::Enveloped::new(bevy_serde_lens::format::Ron),
+        )
+        .unwrap();
+    let mut world = World::new();
+    world.load_from_path::<SaveData>(&path).unwrap();
+    assert_eq!(names(&mut world), vec!["Alice", "Bob"]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
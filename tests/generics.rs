@@ -0,0 +1,106 @@
+use bevy::ecs::{component::Component, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::{BevyObject, WorldExtension};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+pub trait Faction: Component + Serialize + DeserializeOwned + TypePath {}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Red;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Blue;
+
+impl Faction for Red {}
+impl Faction for Blue {}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Hp(u32);
+
+#[derive(BevyObject)]
+pub struct Unit<F: Faction> {
+    hp: Hp,
+    marker: F,
+}
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct QueryUnit<F>(Hp, F)
+where
+    F: Faction;
+
+#[derive(BevyObject)]
+pub struct Marker<F: Faction>(F);
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct QueryMarker<F: Faction>(F);
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    world.spawn((Hp(1), Red));
+    world.spawn((Hp(2), Blue));
+    world.spawn((Hp(3), Red));
+
+    let red = world
+        .save::<Unit<Red>, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        red,
+        json!([{ "hp": 1, "marker": null }, { "hp": 3, "marker": null }])
+    );
+    let blue = world
+        .save::<Unit<Blue>, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(blue, json!([{ "hp": 2, "marker": null }]));
+
+    world.despawn_bound_objects::<Unit<Red>>();
+    assert_eq!(world.entity_count(), 1);
+    world.load::<Unit<Red>, _>(red).unwrap();
+    let mut query = world.query::<(&Hp, &Red)>();
+    assert_eq!(query.iter(&world).count(), 2);
+}
+
+#[test]
+pub fn test_query() {
+    let mut world = World::new();
+    world.spawn((Hp(1), Red));
+    world.spawn((Hp(2), Blue));
+
+    let value = world
+        .save::<QueryUnit<Blue>, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(value, json!([[2, null]]));
+
+    world.despawn_bound_objects::<QueryUnit<Blue>>();
+    assert_eq!(world.entity_count(), 1);
+    world.load::<QueryUnit<Blue>, _>(value).unwrap();
+    let mut query = world.query::<(&Hp, &Blue)>();
+    assert_eq!(query.single(&world).unwrap(), (&Hp(2), &Blue));
+}
+
+#[test]
+pub fn test_newtype() {
+    let mut world = World::new();
+    world.spawn(Hp(1));
+    world.spawn(Blue);
+
+    // Single field tuple structs serialize as their field.
+    let value = world
+        .save::<Marker<Blue>, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(value, json!([null]));
+    let value = world
+        .save::<QueryMarker<Blue>, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(value, json!([null]));
+
+    world.despawn_bound_objects::<Marker<Blue>>();
+    assert_eq!(world.entity_count(), 1);
+    world.load::<Marker<Blue>, _>(value).unwrap();
+    let mut query = world.query::<&Blue>();
+    assert_eq!(query.iter(&world).count(), 1);
+}
@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 pub struct Flag(u64);
 
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct FlagsServer {
     i2s: Vec<String>,
     s2i: FxHashMap<String, u64>,
@@ -76,6 +76,7 @@ pub struct FlagComponent {
 #[test]
 pub fn test() {
     let mut world = World::new();
+    world.register_rollback::<FlagsServer>();
     let mut server = FlagsServer::default();
     let flag1 = server.add("red|green|blue".into()).unwrap();
     let flag2 = server.add("yellow|red".into()).unwrap();
@@ -125,4 +126,12 @@ pub fn test() {
             "red|white|black",
         ])
     );
+
+    assert!(
+        world
+            .load::<FlagComponent, _>(&json!(["purple", 1]))
+            .is_err()
+    );
+    assert!(!world.resource::<FlagsServer>().s2i.contains_key("purple"));
+    assert_eq!(world.resource::<FlagsServer>().i2s.len(), 7);
 }
@@ -0,0 +1,67 @@
+use bevy::ecs::{component::Component, hierarchy::Children, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::{BevyObject, ChildVec, WorldExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Name(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Potion(u32);
+
+#[derive(BevyObject)]
+pub struct Character {
+    name: Name,
+    #[serde(default)]
+    potions: ChildVec<Potion>,
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    let save = json!([
+        {"name": "Alice", "potions": [1, "corrupt", 3]},
+        {"name": 42},
+        {"name": "Bob"},
+    ]);
+
+    // Not lenient by default.
+    assert!(world.load::<Character, _>(&save).is_err());
+    assert_eq!(world.entity_count(), 0);
+
+    let diagnostics = world.load_lenient::<Character, _>(&save).unwrap();
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0].path.to_string(), "[0][1].Potion");
+    assert_eq!(diagnostics[1].path.to_string(), "[1].Name");
+
+    let mut query = world.query::<(&Name, Option<&Children>)>();
+    let mut characters = query
+        .iter(&world)
+        .map(|(name, children)| {
+            let potions = children
+                .into_iter()
+                .flatten()
+                .map(|e| world.entity(*e).get::<Potion>().unwrap().0)
+                .collect::<Vec<_>>();
+            (name.0.clone(), potions)
+        })
+        .collect::<Vec<_>>();
+    characters.sort();
+    assert_eq!(
+        characters,
+        vec![("Alice".to_owned(), vec![1, 3]), ("Bob".to_owned(), vec![]),]
+    );
+    // Alice, Bob and 2 potions.
+    assert_eq!(world.entity_count(), 4);
+
+    // Syntax errors are not recoverable.
+    assert!(
+        world
+            .load_lenient::<Character, _>(&mut serde_json::Deserializer::from_str("[{"))
+            .is_err()
+    );
+    assert_eq!(world.entity_count(), 4);
+}
@@ -0,0 +1,144 @@
+use bevy::ecs::{component::Component, entity::Entity, hierarchy::Children, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::{BevyObject, ChildVec, DefaultInit, Unmatched, WorldExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Id(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Potion(String);
+
+#[derive(BevyObject)]
+pub struct Unit {
+    id: Id,
+    hp: Hp,
+    #[serde(default)]
+    potions: ChildVec<Potion>,
+}
+
+#[derive(BevyObject)]
+pub struct KeyLast {
+    hp: Hp,
+    #[serde(default)]
+    potions: ChildVec<Potion>,
+    id: Id,
+}
+
+#[derive(Debug, Default, PartialEq, Component, TypePath)]
+pub struct Level(u32);
+
+#[derive(BevyObject)]
+pub struct Defaulted {
+    id: Id,
+    hp: Hp,
+    #[serde(default)]
+    level: DefaultInit<Level>,
+}
+
+fn find(world: &mut World, id: u32) -> Option<Entity> {
+    let mut query = world.query::<(Entity, &Id)>();
+    query
+        .iter(world)
+        .find(|(_, x)| x.0 == id)
+        .map(|(entity, _)| entity)
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    let a = world.spawn((Id(1), Hp(10))).id();
+    let b = world
+        .spawn((Id(2), Hp(20)))
+        .with_children(|b| {
+            b.spawn(Potion("Hp Potion".to_owned()));
+        })
+        .id();
+    let c = world.spawn((Id(3), Hp(30))).id();
+
+    world
+        .load_merge::<Unit, Id, _>(
+            json!([
+                {"id": 1, "hp": 5},
+                {"id": 2, "hp": 15, "potions": ["Mp Potion"]},
+                {"id": 4, "hp": 40},
+            ]),
+            Unmatched::Keep,
+        )
+        .unwrap();
+
+    assert_eq!(find(&mut world, 1), Some(a));
+    assert_eq!(find(&mut world, 2), Some(b));
+    assert_eq!(find(&mut world, 3), Some(c));
+    let d = find(&mut world, 4).unwrap();
+
+    assert_eq!(world.entity(a).get::<Hp>(), Some(&Hp(5)));
+    assert_eq!(world.entity(b).get::<Hp>(), Some(&Hp(15)));
+    assert_eq!(world.entity(c).get::<Hp>(), Some(&Hp(30)));
+    assert_eq!(world.entity(d).get::<Hp>(), Some(&Hp(40)));
+
+    let children = world.entity(b).get::<Children>().unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(
+        world.entity(children[0]).get::<Potion>(),
+        Some(&Potion("Mp Potion".to_owned()))
+    );
+    // a, b, c, d and one potion.
+    assert_eq!(world.entity_count(), 5);
+
+    world
+        .load_merge::<Unit, Id, _>(json!([{"id": 2, "hp": 25}]), Unmatched::Despawn)
+        .unwrap();
+
+    assert_eq!(find(&mut world, 2), Some(b));
+    assert_eq!(world.entity(b).get::<Hp>(), Some(&Hp(25)));
+    assert_eq!(world.entity_count(), 1);
+
+    world
+        .load_merge::<KeyLast, Id, _>(
+            json!([{"hp": 1, "potions": ["Elixir"], "id": 2}]),
+            Unmatched::Keep,
+        )
+        .unwrap();
+    assert_eq!(find(&mut world, 2), Some(b));
+    assert_eq!(world.entity(b).get::<Hp>(), Some(&Hp(1)));
+    let children = world.entity(b).get::<Children>().unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(
+        world.entity(children[0]).get::<Potion>(),
+        Some(&Potion("Elixir".to_owned()))
+    );
+    assert_eq!(world.entity_count(), 2);
+
+    assert!(
+        world
+            .load_merge::<KeyLast, Id, _>(json!([{"hp": 7, "id": 2}, {"hp": 1}]), Unmatched::Keep)
+            .is_err()
+    );
+    assert_eq!(world.entity(b).get::<Hp>(), Some(&Hp(1)));
+    assert_eq!(world.entity_count(), 2);
+
+    // Defaulted components are rolled back like deserialized ones.
+    world.entity_mut(b).insert(Level(5));
+    assert!(
+        world
+            .load_merge::<Defaulted, Id, _>(json!([{"id": 2, "hp": 3}, {"hp": 1}]), Unmatched::Keep)
+            .is_err()
+    );
+    assert_eq!(world.entity(b).get::<Hp>(), Some(&Hp(1)));
+    assert_eq!(world.entity(b).get::<Level>(), Some(&Level(5)));
+
+    world
+        .load_merge::<Defaulted, Id, _>(json!([{"id": 2, "hp": 3}]), Unmatched::Keep)
+        .unwrap();
+    assert_eq!(world.entity(b).get::<Level>(), Some(&Level(0)));
+    // The potion is despawned since `Defaulted` has no children.
+    assert_eq!(world.entity_count(), 1);
+}
@@ -0,0 +1,155 @@
+use bevy::ecs::{component::Component, resource::Resource, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::value::Value;
+use bevy_serde_lens::{BevyObject, SerializeResource, WorldExtension, batch};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Name(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Hp {
+    current: u32,
+    max: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Resource, TypePath)]
+pub struct Weather {
+    kind: String,
+    wind: f32,
+}
+
+#[derive(BevyObject)]
+pub struct Unit {
+    name: Name,
+    hp: Hp,
+}
+
+type SaveFile = batch!(Unit, SerializeResource<Weather>);
+
+fn register(world: &mut World) {
+    // Version 0 to 1: `health` is renamed to `hp`.
+    world.register_migration("Unit", 0, |value| {
+        for unit in value.as_seq_mut().into_iter().flatten() {
+            unit.rename("health", "hp");
+        }
+        Ok(())
+    });
+    // Version 1 to 2: `hp` is split into `current` and `max`.
+    world.register_migration("Unit", 1, |value| {
+        for unit in value.as_seq_mut().into_iter().flatten() {
+            let hp = unit.remove("hp").ok_or("missing hp")?;
+            unit.insert(
+                "hp",
+                Value::Map(vec![("current".into(), hp.clone()), ("max".into(), hp)]),
+            );
+        }
+        Ok(())
+    });
+    world.register_migration_alias("Soldier", "Unit");
+    // Version 0 to 1: `Weather` was a string.
+    world.register_migration("Weather", 0, |value| {
+        let kind = std::mem::take(value);
+        *value = Value::Map(vec![
+            ("kind".into(), kind),
+            ("wind".into(), Value::F64(0.0)),
+        ]);
+        Ok(())
+    });
+}
+
+fn hp(world: &mut World, name: &str) -> Option<(u32, u32)> {
+    let mut query = world.query::<(&Name, &Hp)>();
+    query
+        .iter(world)
+        .find(|(n, _)| n.0 == name)
+        .map(|(_, hp)| (hp.current, hp.max))
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    register(&mut world);
+
+    // Saves without a header are version 0.
+    world
+        .load_versioned::<SaveFile, _>(json!({
+            "Soldier": [
+                { "name": "Alice", "health": 5 },
+                { "name": "Bob", "health": 8 },
+            ],
+            "Weather": "Rain",
+        }))
+        .unwrap();
+
+    assert_eq!(hp(&mut world, "Alice"), Some((5, 5)));
+    assert_eq!(hp(&mut world, "Bob"), Some((8, 8)));
+    assert_eq!(
+        world.resource::<Weather>(),
+        &Weather {
+            kind: "Rain".to_owned(),
+            wind: 0.0
+        }
+    );
+
+    let value = world
+        .save_versioned::<SaveFile, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "versions": { "Unit": 2, "Weather": 1 },
+            "data": {
+                "Unit": [
+                    { "name": "Alice", "hp": { "current": 5, "max": 5 } },
+                    { "name": "Bob", "hp": { "current": 8, "max": 8 } },
+                ],
+                "Weather": { "kind": "Rain", "wind": 0.0 },
+            },
+        })
+    );
+
+    // Current versions are loaded as is.
+    world.despawn_bound_objects::<SaveFile>();
+    world.load_versioned::<SaveFile, _>(&value).unwrap();
+    assert_eq!(hp(&mut world, "Alice"), Some((5, 5)));
+    assert_eq!(hp(&mut world, "Bob"), Some((8, 8)));
+
+    // Version 1 only runs the second migration.
+    world.despawn_bound_objects::<SaveFile>();
+    world
+        .load_versioned::<SaveFile, _>(json!({
+            "versions": { "Unit": 1, "Weather": 1 },
+            "data": {
+                "Unit": [{ "name": "Carol", "hp": 3 }],
+                "Weather": { "kind": "Snow", "wind": 2.0 },
+            },
+        }))
+        .unwrap();
+    assert_eq!(hp(&mut world, "Carol"), Some((3, 3)));
+    assert_eq!(world.resource::<Weather>().wind, 2.0);
+
+    // Newer saves are rejected.
+    world.despawn_bound_objects::<SaveFile>();
+    assert!(
+        world
+            .load_versioned::<SaveFile, _>(json!({
+                "versions": { "Unit": 3 },
+                "data": { "Unit": [] },
+            }))
+            .is_err()
+    );
+
+    // Failed migrations are reported.
+    assert!(
+        world
+            .load_versioned::<SaveFile, _>(json!({
+                "versions": { "Unit": 1 },
+                "data": { "Unit": [{ "name": "Dan" }] },
+            }))
+            .is_err()
+    );
+    assert_eq!(world.entity_count(), 0);
+}
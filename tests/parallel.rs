@@ -0,0 +1,152 @@
+use bevy::ecs::{component::Component, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::format::{DeserializeFn, FormatError, ParallelFormat, SaveFormat};
+use bevy_serde_lens::{BevyObject, WorldExtension};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, TypePath)]
+pub struct Json;
+
+impl SaveFormat for Json {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        f(&mut <dyn erased_serde::Deserializer>::erase(
+            &mut deserializer,
+        ))?;
+        deserializer.end()?;
+        Ok(())
+    }
+}
+
+impl ParallelFormat for Json {
+    fn serialize_element(
+        &self,
+        value: &dyn erased_serde::Serialize,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), FormatError> {
+        if !buffer.is_empty() {
+            buffer.push(b',');
+        }
+        serde_json::to_writer(buffer, value)?;
+        Ok(())
+    }
+
+    fn join_seq(&self, _: usize, chunks: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut result = b"[".to_vec();
+        for chunk in chunks {
+            if result.len() > 1 {
+                result.push(b',');
+            }
+            result.extend(chunk);
+        }
+        result.push(b']');
+        result
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Mp(u32);
+
+#[derive(BevyObject)]
+#[bevy_object(parallel)]
+pub struct Unit {
+    hp: Hp,
+    mp: Mp,
+}
+
+fn stats(world: &mut World) -> Vec<(u32, u32)> {
+    let mut query = world.query::<(&Hp, &Mp)>();
+    query.iter(world).map(|(hp, mp)| (hp.0, mp.0)).collect()
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    world.spawn_batch((0..10000).map(|i| (Hp(i), Mp(i * 2))));
+    let expected = stats(&mut world);
+
+    let bytes = world.save_parallel::<Unit>(Json).unwrap();
+    assert_eq!(bytes, world.save_to_bytes::<Unit>(Json).unwrap());
+
+    world.despawn_bound_objects::<Unit>();
+    assert_eq!(world.entity_count(), 0);
+    world.load_from_bytes::<Unit>(&bytes, Json).unwrap();
+    assert_eq!(stats(&mut world), expected);
+
+    // Small saves are serialized sequentially.
+    let mut world = World::new();
+    world.spawn((Hp(1), Mp(2)));
+    let bytes = world.save_parallel::<Unit>(Json).unwrap();
+    assert_eq!(bytes, b"[{\"hp\":1,\"mp\":2}]");
+
+    let mut world = World::new();
+    let bytes = world.save_parallel::<Unit>(Json).unwrap();
+    assert_eq!(bytes, b"[]");
+}
+
+#[test]
+pub fn test_load() {
+    let mut world = World::new();
+    world.spawn_batch((0..10000).map(|i| (Hp(i), Mp(i * 2))));
+    let expected = stats(&mut world);
+    let bytes = world.save_to_bytes::<Unit>(Json).unwrap();
+
+    // `split_seq` is not implemented, deserialized on a single thread.
+    let mut world = World::new();
+    world.load_parallel::<Unit>(&bytes, Json).unwrap();
+    assert_eq!(stats(&mut world), expected);
+
+    let mut world = World::new();
+    assert!(
+        world
+            .load_parallel::<Unit>(b"[{\"hp\":1,\"mp\":2},{\"hp\":1}]", Json)
+            .is_err()
+    );
+    assert_eq!(world.entity_count(), 0);
+}
+
+#[cfg(feature = "json")]
+#[test]
+pub fn test_load_json() {
+    use bevy_serde_lens::format::Json;
+    let mut world = World::new();
+    world.spawn_batch((0..10000).map(|i| (Hp(i), Mp(i * 2))));
+    let expected = stats(&mut world);
+    let bytes = world.save_to_bytes::<Unit>(Json).unwrap();
+
+    let mut world = World::new();
+    world.load_parallel::<Unit>(&bytes, Json).unwrap();
+    assert_eq!(stats(&mut world), expected);
+
+    let mut world = World::new();
+    world.load_parallel::<Unit>(b" [ ] ", Json).unwrap();
+    assert_eq!(world.entity_count(), 0);
+
+    let mut world = World::new();
+    let error = world
+        .load_parallel::<Unit>(b"[{\"hp\":1,\"mp\":2}, {\"hp\":\"[\"}]", Json)
+        .unwrap_err();
+    assert_eq!(error.path.to_string(), "[1]");
+    assert_eq!(world.entity_count(), 0);
+}
+
+#[cfg(feature = "postcard")]
+#[test]
+pub fn test_postcard() {
+    use bevy_serde_lens::format::Postcard;
+    let mut world = World::new();
+    world.spawn_batch((0..10000).map(|i| (Hp(i), Mp(i * 2))));
+    let bytes = world.save_parallel::<Unit>(Postcard).unwrap();
+    assert_eq!(bytes, world.save_to_bytes::<Unit>(Postcard).unwrap());
+}
@@ -0,0 +1,80 @@
+use bevy::ecs::reflect::AppTypeRegistry;
+use bevy::ecs::{component::Component, world::World};
+use bevy::reflect::{Reflect, TypePath};
+use bevy_serde_lens::reflect::ReflectComponent;
+use bevy_serde_lens::{BevyObject, WorldExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Component, Reflect)]
+pub struct Velocity {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Debug, PartialEq, Component, Reflect)]
+pub struct Unregistered(u32);
+
+#[derive(BevyObject)]
+pub struct Unit {
+    hp: Hp,
+    velocity: ReflectComponent<Velocity>,
+}
+
+#[derive(BevyObject)]
+pub struct Invalid {
+    hp: Hp,
+    value: ReflectComponent<Unregistered>,
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    world.init_resource::<AppTypeRegistry>();
+    world
+        .resource::<AppTypeRegistry>()
+        .write()
+        .register::<Velocity>();
+    world.spawn((Hp(1), Velocity { x: 1.0, y: 2.0 }));
+
+    let value = world
+        .save::<Unit, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(
+        value,
+        json!([{ "hp": 1, "velocity": { "x": 1.0, "y": 2.0 } }])
+    );
+
+    world.despawn_bound_objects::<Unit>();
+    assert_eq!(world.entity_count(), 0);
+
+    world.load::<Unit, _>(value).unwrap();
+    let mut query = world.query::<(&Hp, &Velocity)>();
+    let (hp, velocity) = query.single(&world).unwrap();
+    assert_eq!(hp, &Hp(1));
+    assert_eq!(velocity, &Velocity { x: 1.0, y: 2.0 });
+}
+
+#[test]
+pub fn test_unregistered() {
+    let mut world = World::new();
+    world.init_resource::<AppTypeRegistry>();
+    assert!(
+        world
+            .load::<Invalid, _>(json!([{"hp": 1, "value": 2}]))
+            .is_err()
+    );
+    assert_eq!(world.entity_count(), 0);
+
+    // No registry in the world.
+    let mut world = World::new();
+    assert!(
+        world
+            .load::<Invalid, _>(json!([{"hp": 1, "value": 2}]))
+            .is_err()
+    );
+    assert_eq!(world.entity_count(), 0);
+}
@@ -0,0 +1,153 @@
+use bevy::ecs::{component::Component, entity::Entity, hierarchy::Children, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::format::{DeserializeFn, FormatError, SaveFormat};
+use bevy_serde_lens::replication::{ReplicaMap, ReplicationExtension, Snapshot};
+use bevy_serde_lens::{BevyObject, ChildVec, SerializeResource, batch};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, TypePath)]
+pub struct Postcard;
+
+impl SaveFormat for Postcard {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["bin"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Ok(postcard::to_allocvec(value)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let mut deserializer = postcard::Deserializer::from_bytes(bytes);
+        f(&mut <dyn erased_serde::Deserializer>::erase(
+            &mut deserializer,
+        ))?;
+        if !deserializer.finalize()?.is_empty() {
+            return Err("trailing bytes.".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Potion(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, bevy::ecs::resource::Resource, TypePath)]
+#[serde(transparent)]
+pub struct Turn(u32);
+
+#[derive(BevyObject)]
+pub struct Unit {
+    hp: Hp,
+    potions: ChildVec<Potion>,
+}
+
+type Replicated = batch!(Unit, SerializeResource<Turn>);
+
+fn local(client: &World, remote: Entity) -> Entity {
+    client.resource::<ReplicaMap>().get(remote).unwrap()
+}
+
+fn send(server: &mut World, tick: u64) -> Snapshot {
+    let snapshot = server.snapshot::<Replicated>(tick, Postcard).unwrap();
+    let bytes = snapshot.to_bytes();
+    let received = Snapshot::from_bytes(&bytes).unwrap();
+    assert_eq!(received, snapshot);
+    received
+}
+
+#[test]
+pub fn test() {
+    let mut server = World::new();
+    let a = server.spawn(Hp(10)).id();
+    let b = server
+        .spawn(Hp(20))
+        .with_children(|b| {
+            b.spawn(Potion("Hp Potion".to_owned()));
+        })
+        .id();
+    server.insert_resource(Turn(1));
+
+    let mut client = World::new();
+    assert!(
+        client
+            .apply_snapshot::<Replicated>(&send(&mut server, 1), Postcard)
+            .unwrap()
+    );
+    let a_local = local(&client, a);
+    let b_local = local(&client, b);
+    assert_eq!(client.entity(a_local).get::<Hp>(), Some(&Hp(10)));
+    assert_eq!(client.entity(b_local).get::<Hp>(), Some(&Hp(20)));
+    assert_eq!(client.entity(b_local).get::<Children>().unwrap().len(), 1);
+    // a, b and one potion.
+    assert_eq!(client.entity_count(), 3);
+
+    server.entity_mut(a).get_mut::<Hp>().unwrap().0 = 15;
+    server.entity_mut(a).with_children(|a| {
+        a.spawn(Potion("Mp Potion".to_owned()));
+    });
+    server.despawn(b);
+    let c = server.spawn(Hp(30)).id();
+    server.resource_mut::<Turn>().0 = 2;
+
+    let snapshot = send(&mut server, 2);
+    assert!(
+        client
+            .apply_snapshot::<Replicated>(&snapshot, Postcard)
+            .unwrap()
+    );
+    // Updated in place.
+    assert_eq!(local(&client, a), a_local);
+    assert_eq!(client.entity(a_local).get::<Hp>(), Some(&Hp(15)));
+    let children = client.entity(a_local).get::<Children>().unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(
+        client.entity(children[0]).get::<Potion>(),
+        Some(&Potion("Mp Potion".to_owned()))
+    );
+    // Despawned with its children.
+    assert!(client.resource::<ReplicaMap>().get(b).is_none());
+    assert!(client.get_entity(b_local).is_err());
+    let c_local = local(&client, c);
+    assert_eq!(client.entity(c_local).get::<Hp>(), Some(&Hp(30)));
+    assert_eq!(client.resource::<Turn>(), &Turn(2));
+    // a, c and one potion.
+    assert_eq!(client.entity_count(), 3);
+
+    // Applying the same snapshot again does not respawn anything.
+    assert!(
+        !client
+            .apply_snapshot::<Replicated>(&snapshot, Postcard)
+            .unwrap()
+    );
+    assert_eq!(client.resource::<ReplicaMap>().tick(), Some(2));
+
+    // Failed snapshots are rolled back.
+    server.entity_mut(a).get_mut::<Hp>().unwrap().0 = 5;
+    server.spawn(Hp(40));
+    let mut snapshot = send(&mut server, 3);
+    snapshot.data.truncate(snapshot.data.len() - 2);
+    assert!(
+        client
+            .apply_snapshot::<Replicated>(&snapshot, Postcard)
+            .is_err()
+    );
+    assert_eq!(client.entity(a_local).get::<Hp>(), Some(&Hp(15)));
+    assert_eq!(client.resource::<ReplicaMap>().len(), 2);
+    assert_eq!(client.resource::<ReplicaMap>().tick(), Some(2));
+    assert_eq!(client.entity_count(), 3);
+
+    let snapshot = send(&mut server, 4);
+    assert!(
+        client
+            .apply_snapshot::<Replicated>(&snapshot, Postcard)
+            .unwrap()
+    );
+    assert_eq!(client.entity(a_local).get::<Hp>(), Some(&Hp(5)));
+    assert_eq!(client.resource::<ReplicaMap>().len(), 3);
+}
@@ -0,0 +1,118 @@
+use bevy::MinimalPlugins;
+use bevy::app::App;
+use bevy::asset::{AssetPlugin, Assets, Handle};
+use bevy::ecs::message::Messages;
+use bevy::ecs::{component::Component, resource::Resource, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::format::{DeserializeFn, FormatError, SaveFormat};
+use bevy_serde_lens::save_file::{LoadSaveFile, SaveFile, SaveFileLoaded, SaveFilePlugin};
+use bevy_serde_lens::{BevyObject, SerializeResource, batch};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, TypePath)]
+pub struct Json;
+
+impl SaveFormat for Json {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["json"]
+    }
+
+    fn serialize(&self, value: &dyn erased_serde::Serialize) -> Result<Vec<u8>, FormatError> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn deserialize(&self, bytes: &[u8], f: &mut DeserializeFn) -> Result<(), FormatError> {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        f(&mut <dyn erased_serde::Deserializer>::erase(
+            &mut deserializer,
+        ))?;
+        deserializer.end()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Name(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Resource, TypePath)]
+#[serde(transparent)]
+pub struct Turn(u32);
+
+#[derive(BevyObject)]
+pub struct Unit {
+    name: Name,
+}
+
+type SaveData = batch!(Unit, SerializeResource<Turn>);
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    world.spawn(Name("Alice".to_owned()));
+    world.insert_resource(Turn(4));
+
+    let save = SaveFile::<SaveData>::new(&mut world).unwrap();
+    let bytes = save.to_bytes(&Json).unwrap();
+    let parsed = SaveFile::from_bytes(&bytes, &Json).unwrap();
+    assert_eq!(parsed.value, save.value);
+
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .add_plugins(SaveFilePlugin::<SaveData, Json>::default());
+
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<SaveFile<SaveData>>>()
+        .add(parsed);
+    app.world_mut()
+        .spawn(LoadSaveFile::<SaveData>::new(handle.clone()));
+    app.update();
+
+    let world = app.world_mut();
+    let loaded = world
+        .resource_mut::<Messages<SaveFileLoaded<SaveData>>>()
+        .drain()
+        .collect::<Vec<_>>();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].handle, handle);
+    assert!(loaded[0].result.is_ok());
+    assert_eq!(world.resource::<Turn>(), &Turn(4));
+    let mut query = world.query::<&Name>();
+    assert_eq!(query.single(world).unwrap(), &Name("Alice".to_owned()));
+    // The request is despawned and the save is kept for other handles.
+    let mut query = world.query::<&LoadSaveFile<SaveData>>();
+    assert_eq!(query.iter(world).count(), 0);
+    assert!(
+        world
+            .resource::<Assets<SaveFile<SaveData>>>()
+            .get(&handle)
+            .is_some()
+    );
+
+    // The same save can be applied again.
+    world.spawn(LoadSaveFile::<SaveData>::new(handle.clone()));
+    app.update();
+    let world = app.world_mut();
+    let loaded = world
+        .resource_mut::<Messages<SaveFileLoaded<SaveData>>>()
+        .drain()
+        .collect::<Vec<_>>();
+    assert_eq!(loaded.len(), 1);
+    assert!(loaded[0].result.is_ok());
+    let mut query = world.query::<&Name>();
+    assert_eq!(query.iter(world).count(), 2);
+
+    // Requests with a handle unknown to the `AssetServer` fail instead of waiting forever.
+    world.spawn(LoadSaveFile::<SaveData>::new(Handle::default()));
+    app.update();
+    let world = app.world_mut();
+    let loaded = world
+        .resource_mut::<Messages<SaveFileLoaded<SaveData>>>()
+        .drain()
+        .collect::<Vec<_>>();
+    assert_eq!(loaded.len(), 1);
+    assert!(loaded[0].result.is_err());
+    let mut query = world.query::<&LoadSaveFile<SaveData>>();
+    assert_eq!(query.iter(world).count(), 0);
+}
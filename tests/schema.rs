@@ -0,0 +1,383 @@
+use std::fmt::Display;
+
+use bevy::asset::{Asset, Handle};
+use bevy::ecs::component::{Mutable, StorageType};
+use bevy::ecs::entity::Entity;
+use bevy::ecs::world::EntityWorldMut;
+use bevy::ecs::{component::Component, resource::Resource, world::World};
+use bevy::reflect::{DynamicTypePath, TypePath};
+use bevy_serde_lens::asset::PathedHandle;
+use bevy_serde_lens::typetagged::{ErasedObject, TypeTagged};
+use bevy_serde_lens::{
+    BevyObject, ChildMap, ChildMapLike, ChildVec, Maybe, SerializeResource, WorldExtension, batch,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Label(String);
+
+#[derive(Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Hp(u32);
+
+#[derive(Serialize, Deserialize, Component, TypePath)]
+pub struct Pos {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Serialize, Deserialize, Component, TypePath)]
+pub enum Kind {
+    Melee,
+    Ranged(u32),
+}
+
+#[derive(Serialize, Deserialize, Resource, TypePath)]
+#[serde(transparent)]
+pub struct Weather(String);
+
+#[derive(BevyObject)]
+pub struct Unit {
+    label: Label,
+    hp: Maybe<Hp>,
+    pos: Pos,
+    kind: Kind,
+    #[serde(default)]
+    items: ChildVec<Item>,
+}
+
+#[derive(BevyObject)]
+pub struct Item {
+    label: Label,
+}
+
+type SaveFile = batch!(Unit, SerializeResource<Weather>);
+
+#[test]
+pub fn test() {
+    let world = World::new();
+    let schema = serde_json::to_value(world.schema::<SaveFile>()).unwrap();
+    assert_eq!(
+        schema,
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "Unit": {
+                    "type": "array",
+                    "items": { "$ref": "#/$defs/Unit" },
+                },
+                "Weather": { "type": "string" },
+            },
+            "$defs": {
+                "Item": {
+                    "type": "object",
+                    "properties": {
+                        "label": { "type": "string" },
+                    },
+                },
+                "Kind": {
+                    "oneOf": [
+                        { "enum": ["Melee"] },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "Ranged": { "type": "integer" },
+                            },
+                            "required": ["Ranged"],
+                            "additionalProperties": false,
+                        },
+                    ],
+                },
+                "Unit": {
+                    "type": "object",
+                    "properties": {
+                        "label": { "type": "string" },
+                        "hp": {
+                            "anyOf": [{ "type": "integer" }, { "type": "null" }],
+                        },
+                        "pos": {
+                            "type": "object",
+                            "properties": {
+                                "x": { "type": "number" },
+                                "y": { "type": "number" },
+                            },
+                        },
+                        "kind": { "$ref": "#/$defs/Kind" },
+                        "items": {
+                            "type": "array",
+                            "items": { "$ref": "#/$defs/Item" },
+                        },
+                    },
+                },
+            },
+        })
+    );
+}
+
+pub trait Animal: DynamicTypePath + Send + Sync + 'static {
+    fn as_ser(&self) -> &dyn erased_serde::Serialize;
+}
+
+impl ErasedObject for Box<dyn Animal> {
+    fn name(&self) -> impl AsRef<str> {
+        self.as_ref().reflect_short_type_path()
+    }
+
+    fn as_serialize(&self) -> &dyn erased_serde::Serialize {
+        self.as_ser()
+    }
+}
+
+impl TypePath for Box<dyn Animal> {
+    fn type_path() -> &'static str {
+        "Animal"
+    }
+
+    fn short_type_path() -> &'static str {
+        "Animal"
+    }
+}
+
+impl Component for Box<dyn Animal> {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+    type Mutability = Mutable;
+}
+
+#[derive(Serialize, Deserialize, TypePath)]
+pub struct Dog {
+    name: String,
+}
+
+impl Animal for Dog {
+    fn as_ser(&self) -> &dyn erased_serde::Serialize {
+        self
+    }
+}
+
+impl From<Dog> for Box<dyn Animal> {
+    fn from(value: Dog) -> Self {
+        Box::new(value)
+    }
+}
+
+#[derive(Serialize, Deserialize, Asset, TypePath)]
+#[serde(transparent)]
+pub struct Image(String);
+
+#[derive(Serialize, Deserialize, Component, TypePath)]
+pub struct Sprite {
+    #[serde(with = "PathedHandle")]
+    image: Handle<Image>,
+}
+
+#[derive(Component, Default)]
+pub struct Slots(Vec<(String, Entity)>);
+
+impl ChildMapLike for Slots {
+    type Key = String;
+
+    fn iter_children(&self) -> impl Iterator<Item = (&Self::Key, Entity)> {
+        self.0.iter().map(|(key, entity)| (key, *entity))
+    }
+
+    fn add_child(
+        mut parent: EntityWorldMut,
+        key: Self::Key,
+        child: Entity,
+    ) -> Result<(), impl Display> {
+        parent
+            .entry::<Slots>()
+            .or_default()
+            .get_mut()
+            .0
+            .push((key, child));
+        Ok::<_, String>(())
+    }
+}
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct Stats {
+    hp: Hp,
+    pos: Pos,
+}
+
+#[derive(BevyObject)]
+pub struct Flat {
+    label: Label,
+    #[bevy_object(flatten)]
+    stats: Stats,
+}
+
+#[derive(BevyObject)]
+pub struct Pet {
+    label: Label,
+    animal: TypeTagged<Box<dyn Animal>>,
+    sprite: Sprite,
+    slots: ChildMap<Item, Slots>,
+}
+
+#[derive(BevyObject)]
+pub enum Loot {
+    Labeled {
+        label: Label,
+    },
+    #[serde(rename = "stats")]
+    Stats {
+        hp: Hp,
+        #[serde(default)]
+        pos: Maybe<Pos>,
+    },
+}
+
+#[test]
+pub fn test_enum() {
+    let world = World::new();
+    let schema = serde_json::to_value(world.schema::<Loot>()).unwrap();
+    assert_eq!(
+        schema,
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "array",
+            "items": { "$ref": "#/$defs/Loot" },
+            "$defs": {
+                "Loot": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "properties": {
+                                "Labeled": {
+                                    "type": "object",
+                                    "properties": {
+                                        "label": { "type": "string" },
+                                    },
+                                },
+                            },
+                            "required": ["Labeled"],
+                            "additionalProperties": false,
+                        },
+                        {
+                            "type": "object",
+                            "properties": {
+                                "stats": {
+                                    "type": "object",
+                                    "properties": {
+                                        "hp": { "type": "integer" },
+                                        "pos": {
+                                            "anyOf": [
+                                                {
+                                                    "type": "object",
+                                                    "properties": {
+                                                        "x": { "type": "number" },
+                                                        "y": { "type": "number" },
+                                                    },
+                                                },
+                                                { "type": "null" },
+                                            ],
+                                        },
+                                    },
+                                },
+                            },
+                            "required": ["stats"],
+                            "additionalProperties": false,
+                        },
+                    ],
+                },
+            },
+        })
+    );
+}
+
+#[test]
+pub fn test_extractors() {
+    let mut world = World::new();
+    world.register_typetag::<Box<dyn Animal>, Dog>();
+    let schema = serde_json::to_value(world.schema::<batch!(Pet, Flat)>()).unwrap();
+    assert_eq!(
+        schema,
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "type": "object",
+            "properties": {
+                "Pet": {
+                    "type": "array",
+                    "items": { "$ref": "#/$defs/Pet" },
+                },
+                "Flat": {
+                    "type": "array",
+                    "items": { "$ref": "#/$defs/Flat" },
+                },
+            },
+            "$defs": {
+                // `#[serde(flatten)]` cannot be traced.
+                "Flat": {},
+                "Id": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "properties": { "Path": { "type": "string" } },
+                            "required": ["Path"],
+                            "additionalProperties": false,
+                        },
+                        {
+                            "type": "object",
+                            "properties": { "Index": { "type": "integer" } },
+                            "required": ["Index"],
+                            "additionalProperties": false,
+                        },
+                    ],
+                },
+                "Item": {
+                    "type": "object",
+                    "properties": {
+                        "label": { "type": "string" },
+                    },
+                },
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "label": { "type": "string" },
+                        "animal": {
+                            "oneOf": [
+                                {
+                                    "type": "object",
+                                    "properties": {
+                                        "Dog": {
+                                            "type": "object",
+                                            "properties": {
+                                                "name": { "type": "string" },
+                                            },
+                                        },
+                                    },
+                                    "required": ["Dog"],
+                                    "additionalProperties": false,
+                                },
+                            ],
+                        },
+                        "sprite": {
+                            "type": "object",
+                            "properties": {
+                                "image": {
+                                    "type": "object",
+                                    "properties": {
+                                        "index": { "$ref": "#/$defs/Id" },
+                                        "asset": {
+                                            "anyOf": [{ "type": "string" }, { "type": "null" }],
+                                        },
+                                    },
+                                },
+                            },
+                        },
+                        "slots": {
+                            "type": "object",
+                            "additionalProperties": { "$ref": "#/$defs/Item" },
+                        },
+                    },
+                },
+            },
+        })
+    );
+}
@@ -0,0 +1,108 @@
+use bevy::ecs::{component::Component, hierarchy::Children, resource::Resource, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::{BevyObject, ChildVec, SerializeResource, Unmatched, WorldExtension, batch};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Id(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Hp(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+#[serde(transparent)]
+pub struct Potion(String);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Resource, TypePath)]
+#[serde(transparent)]
+pub struct Turn(u32);
+
+#[derive(BevyObject)]
+pub struct Unit {
+    id: Id,
+    hp: Hp,
+    #[serde(default)]
+    potions: ChildVec<Potion>,
+}
+
+type SaveFile = batch!(SerializeResource<Turn>, Unit);
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    world.insert_resource(Turn(1));
+
+    // The second unit fails, the first unit, its children and the resource are rolled back.
+    assert!(
+        world
+            .load::<SaveFile, _>(json!({
+                "Turn": 2,
+                "Unit": [
+                    {"id": 1, "hp": 10, "potions": ["Hp Potion", "Mp Potion"]},
+                    {"id": 2, "hp": "invalid"},
+                ],
+            }))
+            .is_err()
+    );
+    assert_eq!(world.entity_count(), 0);
+    assert_eq!(world.resource::<Turn>(), &Turn(1));
+
+    world
+        .load::<SaveFile, _>(json!({
+            "Turn": 2,
+            "Unit": [{"id": 1, "hp": 10}],
+        }))
+        .unwrap();
+    assert_eq!(world.entity_count(), 1);
+    assert_eq!(world.resource::<Turn>(), &Turn(2));
+}
+
+#[test]
+pub fn test_merge() {
+    let mut world = World::new();
+    let a = world
+        .spawn((Id(1), Hp(10)))
+        .with_children(|a| {
+            a.spawn(Potion("Hp Potion".to_owned()));
+        })
+        .id();
+
+    // Existing entities keep their components and children.
+    assert!(
+        world
+            .load_merge::<Unit, Id, _>(
+                json!([
+                    {"id": 1, "hp": 5, "potions": ["Mp Potion"]},
+                    {"id": 2, "hp": "invalid"},
+                ]),
+                Unmatched::Despawn,
+            )
+            .is_err()
+    );
+    assert_eq!(world.entity(a).get::<Hp>(), Some(&Hp(10)));
+    let children = world.entity(a).get::<Children>().unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(
+        world.entity(children[0]).get::<Potion>(),
+        Some(&Potion("Hp Potion".to_owned()))
+    );
+    assert_eq!(world.entity_count(), 2);
+
+    world
+        .load_merge::<Unit, Id, _>(
+            json!([{"id": 1, "hp": 5, "potions": ["Mp Potion"]}]),
+            Unmatched::Despawn,
+        )
+        .unwrap();
+    assert_eq!(world.entity(a).get::<Hp>(), Some(&Hp(5)));
+    let children = world.entity(a).get::<Children>().unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(
+        world.entity(children[0]).get::<Potion>(),
+        Some(&Potion("Mp Potion".to_owned()))
+    );
+    assert_eq!(world.entity_count(), 2);
+}
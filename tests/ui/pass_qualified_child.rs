@@ -0,0 +1,16 @@
+use bevy::ecs::component::Component;
+use bevy::reflect::TypePath;
+use bevy_serde_lens::BevyObject;
+use serde::{Deserialize, Serialize};
+
+/// A component that shares its name with `bevy_serde_lens::Child`.
+#[derive(Serialize, Deserialize, Component, TypePath)]
+pub struct Child(u32);
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct Unit {
+    child: self::Child,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use bevy::ecs::component::Component;
+use bevy::reflect::TypePath;
+use bevy_serde_lens::{BevyObject, Child, Maybe};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Component, TypePath)]
+pub struct Hp(u32);
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct Unit {
+    hp: Hp,
+    item: Maybe<Child<Hp>>,
+}
+
+fn main() {}
@@ -0,0 +1,72 @@
+use bevy::ecs::{component::Component, world::World};
+use bevy::reflect::TypePath;
+use bevy_serde_lens::{BevyObject, WorldExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Component, TypePath)]
+pub struct Hp(u32);
+
+/// Not serializable.
+#[derive(Debug, PartialEq, Component)]
+pub struct Secret(String);
+
+mod reversed {
+    use super::Secret;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(item: &Secret, serializer: S) -> Result<S::Ok, S::Error> {
+        item.0
+            .chars()
+            .rev()
+            .collect::<String>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Secret, D::Error> {
+        Ok(Secret(
+            String::deserialize(deserializer)?.chars().rev().collect(),
+        ))
+    }
+}
+
+#[derive(BevyObject)]
+pub struct Unit {
+    hp: Hp,
+    #[bevy_object(with = "reversed")]
+    secret: Secret,
+}
+
+#[derive(BevyObject)]
+#[bevy_object(query)]
+pub struct QueryUnit {
+    hp: Hp,
+    #[bevy_object(with = "reversed")]
+    secret: Secret,
+}
+
+#[test]
+pub fn test() {
+    let mut world = World::new();
+    world.spawn((Hp(1), Secret("abc".into())));
+
+    let value = world
+        .save::<Unit, _>(serde_json::value::Serializer)
+        .unwrap();
+    assert_eq!(value, json!([{ "hp": 1, "secret": "cba" }]));
+    assert_eq!(
+        world
+            .save::<QueryUnit, _>(serde_json::value::Serializer)
+            .unwrap(),
+        value
+    );
+
+    world.despawn_bound_objects::<Unit>();
+    assert_eq!(world.entity_count(), 0);
+    world.load::<QueryUnit, _>(value).unwrap();
+    let mut query = world.query::<(&Hp, &Secret)>();
+    assert_eq!(
+        query.single(&world).unwrap(),
+        (&Hp(1), &Secret("abc".into()))
+    );
+}